@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+
+use crate::visit::{Control, EdgeRef, GraphBase, NodeIndexable, OutEdges};
+
+/// A vertex reached by a breadth-first search, see [`Bfs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BfsStep<N> {
+    pub vertex: N,
+    /// The number of edges on the path the search took from the closest source.
+    pub depth: usize,
+    /// The vertex the search came from, or `None` for a source.
+    pub parent: Option<N>,
+}
+
+/// A breadth-first search over any graph implementing the graph traits, as an iterator over the
+/// vertices in the order they are reached. The search can start from several sources at once, in
+/// which case every vertex is reached from the closest one. It panics if a source is not a vertex
+/// of the graph.
+pub struct Bfs<'a, G: GraphBase> {
+    graph: &'a G,
+    queue: VecDeque<BfsStep<G::NodeId>>,
+    discovered: Vec<bool>,
+}
+
+impl<'a, G: NodeIndexable + OutEdges> Bfs<'a, G> {
+    /// A search starting from `source`.
+    pub fn new(graph: &'a G, source: G::NodeId) -> Self {
+        Self::from_sources(graph, Some(source))
+    }
+
+    /// A search starting from all the `sources` at depth 0.
+    pub fn from_sources(graph: &'a G, sources: impl IntoIterator<Item = G::NodeId>) -> Self {
+        let mut bfs = Self {
+            graph,
+            queue: VecDeque::new(),
+            discovered: vec![false; graph.node_bound()],
+        };
+        for source in sources {
+            bfs.discover(source, 0, None);
+        }
+        bfs
+    }
+
+    /// Whether the search has reached `id`, i.e. whether it has been or is about to be yielded.
+    pub fn is_discovered(&self, id: G::NodeId) -> bool {
+        self.discovered[self.graph.to_index(id)]
+    }
+
+    fn discover(&mut self, vertex: G::NodeId, depth: usize, parent: Option<G::NodeId>) {
+        let seen = &mut self.discovered[self.graph.to_index(vertex)];
+        if !*seen {
+            *seen = true;
+            self.queue.push_back(BfsStep {
+                vertex,
+                depth,
+                parent,
+            });
+        }
+    }
+}
+
+impl<G: NodeIndexable + OutEdges> Iterator for Bfs<'_, G> {
+    type Item = BfsStep<G::NodeId>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let step = self.queue.pop_front()?;
+        let graph = self.graph;
+        for to in graph.out_neighbors(step.vertex) {
+            self.discover(to, step.depth + 1, Some(step.vertex));
+        }
+        Some(step)
+    }
+}
+
+/// The events of a breadth-first search reported to the visitor of [`bfs_visit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BfsEvent<N, Ed> {
+    /// The search reached a vertex for the first time. Pruning here keeps the search from
+    /// examining the edges of the vertex.
+    Discover(BfsStep<N>),
+    /// The search is looking at an edge going out of a vertex it took from the queue. Pruning
+    /// here keeps the search from following the edge.
+    ExamineEdge(EdgeRef<N, Ed>),
+    /// The edge leads to a vertex the search had not reached yet, which it is about to discover.
+    /// Pruning here keeps the search from discovering the vertex through this edge.
+    TreeEdge(EdgeRef<N, Ed>),
+    /// The search is done with a vertex: all its edges have been examined.
+    Finish(N),
+}
+
+/// Runs a breadth-first search from `sources`, reporting every step to `visit`, which steers the
+/// search with the [`Control`] it returns. Returns [`Control::Break`] if the visitor stopped the
+/// search and [`Control::Continue`] otherwise.
+pub fn bfs_visit<G, F>(
+    graph: &G,
+    sources: impl IntoIterator<Item = G::NodeId>,
+    mut visit: F,
+) -> Control
+where
+    G: NodeIndexable + OutEdges,
+    F: FnMut(BfsEvent<G::NodeId, G::EdgeId>) -> Control,
+{
+    let mut discovered = vec![false; graph.node_bound()];
+    // The vertices to finish, with whether to examine their edges first.
+    let mut queue = VecDeque::new();
+    for vertex in sources {
+        let step = BfsStep {
+            vertex,
+            depth: 0,
+            parent: None,
+        };
+        if discover(graph, step, &mut discovered, &mut queue, &mut visit) == Control::Break {
+            return Control::Break;
+        }
+    }
+
+    while let Some((step, expand)) = queue.pop_front() {
+        if expand {
+            for edge in graph.out_edge_refs(step.vertex) {
+                match visit(BfsEvent::ExamineEdge(edge)) {
+                    Control::Continue => {}
+                    Control::Prune => continue,
+                    Control::Break => return Control::Break,
+                }
+                if discovered[graph.to_index(edge.target)] {
+                    continue;
+                }
+                match visit(BfsEvent::TreeEdge(edge)) {
+                    Control::Continue => {}
+                    Control::Prune => continue,
+                    Control::Break => return Control::Break,
+                }
+                let next = BfsStep {
+                    vertex: edge.target,
+                    depth: step.depth + 1,
+                    parent: Some(step.vertex),
+                };
+                if discover(graph, next, &mut discovered, &mut queue, &mut visit) == Control::Break
+                {
+                    return Control::Break;
+                }
+            }
+        }
+        if visit(BfsEvent::Finish(step.vertex)) == Control::Break {
+            return Control::Break;
+        }
+    }
+    Control::Continue
+}
+
+/// Reports the discovery of `step.vertex` and queues it, unless it was discovered before.
+fn discover<G, F>(
+    graph: &G,
+    step: BfsStep<G::NodeId>,
+    discovered: &mut [bool],
+    queue: &mut VecDeque<(BfsStep<G::NodeId>, bool)>,
+    visit: &mut F,
+) -> Control
+where
+    G: NodeIndexable,
+    F: FnMut(BfsEvent<G::NodeId, G::EdgeId>) -> Control,
+{
+    let seen = &mut discovered[graph.to_index(step.vertex)];
+    if *seen {
+        return Control::Continue;
+    }
+    *seen = true;
+    let control = visit(BfsEvent::Discover(step));
+    if control != Control::Break {
+        queue.push_back((step, control == Control::Continue));
+    }
+    control
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    #[test]
+    fn bfs_yields_depths_and_parents() {
+        let (g, ids) =
+            graph! { "a" -> "b", "a" -> "c", "b" -> "d", "c" -> "d", "d" -> "e", "f" -> "e" };
+        let steps: Vec<_> = Bfs::new(&g, ids["a"])
+            .map(|s| (g[s.vertex], s.depth, s.parent.map(|p| g[p])))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("a", 0, None),
+                ("b", 1, Some("a")),
+                ("c", 1, Some("a")),
+                ("d", 2, Some("b")),
+                ("e", 3, Some("d")),
+            ]
+        );
+
+        let bfs = Bfs::from_sources(&g, vec![ids["f"], ids["c"], ids["f"]]);
+        let depths: Vec<_> = bfs.map(|s| (g[s.vertex], s.depth)).collect();
+        assert_eq!(depths, vec![("f", 0), ("c", 0), ("e", 1), ("d", 1)]);
+    }
+
+    #[test]
+    fn bfs_visitor_can_prune_and_stop() {
+        let (g, ids) = graph! { "a" -> "b", "a" -> "c", "b" -> "d", "c" -> "e", "e" -> "f" };
+        let mut events = vec![];
+        let control = bfs_visit(&g, Some(ids["a"]), |event| {
+            let (name, control) = match event {
+                BfsEvent::Discover(step) => (format!("discover {}", g[step.vertex]), {
+                    if g[step.vertex] == "b" {
+                        Control::Prune
+                    } else {
+                        Control::Continue
+                    }
+                }),
+                BfsEvent::ExamineEdge(e) => (format!("examine {}", g[e.target]), Control::Continue),
+                BfsEvent::TreeEdge(e) => (format!("tree {}", g[e.target]), Control::Continue),
+                BfsEvent::Finish(v) => (format!("finish {}", g[v]), {
+                    if g[v] == "c" {
+                        Control::Break
+                    } else {
+                        Control::Continue
+                    }
+                }),
+            };
+            events.push(name);
+            control
+        });
+        assert_eq!(control, Control::Break);
+        assert_eq!(
+            events,
+            vec![
+                "discover a",
+                "examine b",
+                "tree b",
+                "discover b",
+                "examine c",
+                "tree c",
+                "discover c",
+                "finish a",
+                "finish b",
+                "examine e",
+                "tree e",
+                "discover e",
+                "finish c",
+            ]
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData};
+
+use crate::{Directed, EdgeType, Graph, GraphError, Policy, Undirected, VertexId};
+
+/// A graph along with the id of each of its named vertices, as built by a [`GraphBuilder`].
+pub type NamedGraph<N, T, E = i32, Ty = Directed> = (Graph<T, E, Ty>, HashMap<N, VertexId>);
+
+/// Creates the data of a vertex out of its name.
+type MakeVertex<N, T> = Box<dyn Fn(&N) -> T>;
+
+/// Builds a [`Graph`] out of named vertices and edges between the names, so that the caller does
+/// not have to keep track of the [`VertexId`]s while wiring things up. Nothing is checked until
+/// [`GraphBuilder::build`], which reports the first problem it runs into.
+pub struct GraphBuilder<N, T, E = i32, Ty = Directed> {
+    vertices: Vec<(N, T)>,
+    edges: Vec<(N, N, E)>,
+    policy: Policy<E>,
+    /// Creates the data of the vertices that are only mentioned by the edges.
+    auto_vertices: Option<MakeVertex<N, T>>,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, T, E> GraphBuilder<N, T, E> {
+    /// Starts building a directed graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N, T, E> GraphBuilder<N, T, E, Undirected> {
+    /// Starts building an undirected graph.
+    pub fn new_undirected() -> Self {
+        Self::default()
+    }
+}
+
+impl<N, T, E, Ty: EdgeType> Default for GraphBuilder<N, T, E, Ty> {
+    fn default() -> Self {
+        Self {
+            vertices: vec![],
+            edges: vec![],
+            policy: Policy::default(),
+            auto_vertices: None,
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<N, T, E, Ty> GraphBuilder<N, T, E, Ty>
+where
+    N: Hash + Eq + Debug,
+    Ty: EdgeType,
+{
+    /// Adds a vertex called `name` storing `data`.
+    pub fn vertex(mut self, name: N, data: T) -> Self {
+        self.vertices.push((name, data));
+        self
+    }
+
+    /// Adds an edge between the vertices called `from` and `to`.
+    pub fn edge(mut self, from: N, to: N, data: E) -> Self {
+        self.edges.push((from, to, data));
+        self
+    }
+
+    /// Adds all the `(from, to, weight)` triples as edges.
+    pub fn edges<I: IntoIterator<Item = (N, N, E)>>(mut self, edges: I) -> Self {
+        self.edges.extend(edges);
+        self
+    }
+
+    /// Sets the policy the graph is built with.
+    pub fn policy(mut self, policy: Policy<E>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Creates the vertices that are mentioned by an edge but were never added, using
+    /// `make_data` to come up with their data. Without this such edges are an error.
+    pub fn auto_vertices<F: Fn(&N) -> T + 'static>(mut self, make_data: F) -> Self {
+        self.auto_vertices = Some(Box::new(make_data));
+        self
+    }
+
+    /// Builds the graph, returning it along with the id of every named vertex. The vertices are
+    /// created in the order they were added, followed by the automatically created ones in the
+    /// order the edges mention them.
+    pub fn build(self) -> Result<NamedGraph<N, T, E, Ty>, GraphError> {
+        let mut graph = Graph::with_policy(self.policy);
+        let mut ids = HashMap::with_capacity(self.vertices.len());
+        for (name, data) in self.vertices {
+            if ids.contains_key(&name) {
+                return Err(GraphError::DuplicateKey(format!("{:?}", name)));
+            }
+            ids.insert(name, graph.create_node(data));
+        }
+
+        let auto_vertices = self.auto_vertices;
+        for (from, to, data) in self.edges {
+            let mut id = |name: N| match (ids.get(&name), &auto_vertices) {
+                (Some(&id), _) => Ok(id),
+                (None, Some(make_data)) => {
+                    let id = graph.create_node(make_data(&name));
+                    ids.insert(name, id);
+                    Ok(id)
+                }
+                (None, None) => Err(GraphError::UnknownKey(format!("{:?}", name))),
+            };
+            let (from_id, to_id) = (id(from)?, id(to)?);
+            graph.add_weighted_edge(from_id, to_id, data)?;
+        }
+        Ok((graph, ids))
+    }
+}
+
+/// Builds a directed multigraph whose vertices store their own names, and returns it along with
+/// the name -> [`VertexId`] map. Every entry is an edge `from -> to`, optionally followed by
+/// `: weight`; edges without a weight get the default one. When none of the edges has a weight,
+/// the weights are `i32`s, like in a plain [`Graph`].
+///
+/// ```
+/// let (g, ids) = graphs::graph! { "a" -> "b" : 3, "b" -> "c" };
+/// assert_eq!(g.edge_count(), 2);
+/// assert_eq!(g[ids["c"]], "c");
+/// ```
+#[macro_export]
+macro_rules! graph {
+    (@weight) => {
+        ::std::default::Default::default()
+    };
+    (@weight $wt:expr) => {
+        $wt
+    };
+    (@build $builder:expr; $($from:tt -> $to:tt $(: $wt:expr)?),*) => {{
+        let builder = $builder.auto_vertices(|name: &&'static str| *name);
+        $(
+            let builder = builder.edge($from, $to, $crate::graph!(@weight $($wt)?));
+        )*
+        builder
+            .build()
+            .expect("a multigraph with automatic vertices accepts every edge")
+    }};
+    ($($from:tt -> $to:tt),* $(,)?) => {
+        $crate::graph!(@build $crate::GraphBuilder::<_, _, i32>::new(); $($from -> $to),*)
+    };
+    ($($from:tt -> $to:tt $(: $wt:expr)?),* $(,)?) => {
+        $crate::graph!(@build $crate::GraphBuilder::new(); $($from -> $to $(: $wt)?),*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GraphBuilder, GraphError, Policy};
+
+    #[test]
+    fn builder_wires_up_names() {
+        let (g, ids) = GraphBuilder::new()
+            .vertex("db", 5432)
+            .vertex("api", 443)
+            .edges(vec![("api", "db", 2), ("api", "api", 1)])
+            .build()
+            .unwrap();
+        assert_eq!(g[ids["db"]], 5432);
+        assert_eq!(g.neighbors(ids["api"]).unwrap().count(), 2);
+
+        let err = GraphBuilder::new()
+            .vertex("db", 5432)
+            .edge("api", "db", 2)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, GraphError::UnknownKey("\"api\"".to_string()));
+
+        let err = GraphBuilder::new()
+            .vertex("db", 1)
+            .vertex("db", 2)
+            .edge("db", "db", 0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err, GraphError::DuplicateKey("\"db\"".to_string()));
+
+        let err = GraphBuilder::new()
+            .vertex("db", 1)
+            .edge("db", "db", 0)
+            .policy(Policy::simple())
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, GraphError::SelfLoop(_)));
+    }
+
+    #[test]
+    fn graph_macro_without_weights() {
+        let (g, ids) = graph! { "a" -> "b", "b" -> "c" };
+        let weights: Vec<i32> = g.edges().map(|(_, _, &wt)| wt).collect();
+        assert_eq!(weights, vec![0, 0]);
+        assert_eq!(g.in_degree(ids["c"]), Ok(1));
+    }
+
+    #[test]
+    fn graph_macro() {
+        let (g, ids) = graph! { "a" -> "b" : 3, "b" -> "c", "c" -> "a" : 1 };
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(
+            g.edges().map(|(_, _, wt)| *wt).collect::<Vec<_>>(),
+            vec![3, 0, 1]
+        );
+        assert_eq!(g[ids["b"]], "b");
+        assert_eq!(
+            g.neighbors(ids["c"]).unwrap().collect::<Vec<_>>(),
+            vec![ids["a"]]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use petgraph::{
+    graph::{EdgeIndex, IndexType, NodeIndex},
+    stable_graph::StableGraph,
+    visit::NodeIndexable,
+};
+
+use crate::{Directed, EdgeId, EdgeMap, EdgeType, Graph, Undirected, Vertex, VertexId, VertexMap};
+
+type Petgraph<T, E, Ty, Ix> = petgraph::Graph<T, E, <Ty as EdgeType>::Petgraph, Ix>;
+
+/// Where the vertices and edges of a [`Graph`] ended up in the petgraph graph it was converted
+/// into. The removed vertices and edges map to `None`.
+#[derive(Debug, Clone)]
+pub struct PetgraphIds<Ix> {
+    pub vertices: VertexMap<Option<NodeIndex<Ix>>>,
+    pub edges: EdgeMap<Option<EdgeIndex<Ix>>>,
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    /// Converts the graph into a petgraph [`Graph`](petgraph::Graph), along with the petgraph
+    /// index of every vertex and edge. The petgraph indices are dense, so they differ from ours
+    /// when the graph has removed vertices or edges.
+    pub fn into_petgraph<Ix: IndexType>(self) -> (Petgraph<T, E, Ty, Ix>, PetgraphIds<Ix>) {
+        let mut ids = PetgraphIds {
+            vertices: VertexMap::new(&self, None),
+            edges: EdgeMap::new(&self, None),
+        };
+        let graph = to_petgraph::<_, _, Ty, _>(
+            self.node_count,
+            self.vertices
+                .into_iter()
+                .flatten()
+                .map(|vx| (vx.id, vx.data)),
+            self.edges
+                .into_iter()
+                .enumerate()
+                .filter_map(|(id, edge)| edge.map(|e| (id, e.from, e.to, e.data))),
+            &mut ids,
+        );
+        (graph, ids)
+    }
+
+    /// Converts the graph into a petgraph [`StableGraph`], along with the petgraph index of every
+    /// vertex and edge, see [`Graph::into_petgraph`]. The graph is compacted on the way, just like
+    /// for a petgraph [`Graph`](petgraph::Graph): the holes left by removed vertices and edges are
+    /// not carried over, so our ids only map to petgraph indices through the returned
+    /// [`PetgraphIds`], and converting back gives a graph with other ids once anything has been
+    /// removed.
+    pub fn into_stable_graph<Ix: IndexType>(
+        self,
+    ) -> (StableGraph<T, E, Ty::Petgraph, Ix>, PetgraphIds<Ix>) {
+        let (graph, ids) = self.into_petgraph();
+        (StableGraph::from(graph), ids)
+    }
+
+    /// A petgraph graph borrowing the data of this one, for the petgraph features that only need
+    /// to look at it.
+    pub(crate) fn as_petgraph(&self) -> Petgraph<&T, &E, Ty, u32> {
+        let mut ids = PetgraphIds {
+            vertices: VertexMap::new(self, None),
+            edges: EdgeMap::new(self, None),
+        };
+        to_petgraph::<_, _, Ty, _>(
+            self.node_count,
+            self.vertices(),
+            self.edges
+                .iter()
+                .enumerate()
+                .filter_map(|(id, edge)| edge.as_ref().map(|e| (id, e.from, e.to, &e.data))),
+            &mut ids,
+        )
+    }
+
+    /// Builds a graph keeping the indices of the vertices and edges, with holes where there is no
+    /// data. The vertices get the generation 0.
+    fn from_slots(
+        vertices: Vec<Option<T>>,
+        edge_bound: usize,
+        edges: impl Iterator<Item = (EdgeId, usize, usize, E)>,
+    ) -> Self {
+        let mut graph = Graph::default();
+        graph.vertices = vertices
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| data.map(|data| Vertex::new(vid(index), data)))
+            .collect();
+        graph.node_count = graph.vertices.iter().flatten().count();
+        graph.edges = (0..edge_bound).map(|_| None).collect();
+        for (id, from, to, data) in edges {
+            graph.link(id, vid(from), vid(to), data);
+        }
+        graph
+    }
+}
+
+fn vid(index: usize) -> VertexId {
+    VertexId {
+        index,
+        generation: 0,
+    }
+}
+
+fn to_petgraph<T, E, Ty, Ix>(
+    node_count: usize,
+    vertices: impl Iterator<Item = (VertexId, T)>,
+    edges: impl Iterator<Item = (EdgeId, VertexId, VertexId, E)>,
+    ids: &mut PetgraphIds<Ix>,
+) -> Petgraph<T, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    let mut graph = Petgraph::<T, E, Ty, Ix>::with_capacity(node_count, 0);
+    for (id, data) in vertices {
+        ids.vertices.insert(id, Some(graph.add_node(data)));
+    }
+    for (id, from, to, data) in edges {
+        let (from, to) = (ids.vertices[from], ids.vertices[to]);
+        let edge = graph.add_edge(
+            from.expect("a live vertex"),
+            to.expect("a live vertex"),
+            data,
+        );
+        ids.edges.insert(id, Some(edge));
+    }
+    graph
+}
+
+/// See [`Graph::into_petgraph`].
+impl<T, E, Ty, Ix> From<Graph<T, E, Ty>> for Petgraph<T, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn from(graph: Graph<T, E, Ty>) -> Self {
+        graph.into_petgraph().0
+    }
+}
+
+/// See [`Graph::into_stable_graph`].
+impl<T, E, Ty, Ix> From<Graph<T, E, Ty>> for StableGraph<T, E, Ty::Petgraph, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    fn from(graph: Graph<T, E, Ty>) -> Self {
+        graph.into_stable_graph().0
+    }
+}
+
+/// Conversions from petgraph graphs, implemented for each edge type so that the type of the
+/// graph can be inferred from the petgraph one.
+macro_rules! from_petgraph {
+    ($ty:ty, $pty:ty) => {
+        /// The petgraph node and edge indices become our vertex indices and edge ids, so the
+        /// vertex with the petgraph index `n` is `graph.vertex_id(n.index())`.
+        impl<T, E, Ix: IndexType> From<petgraph::Graph<T, E, $pty, Ix>> for Graph<T, E, $ty> {
+            fn from(graph: petgraph::Graph<T, E, $pty, Ix>) -> Self {
+                let (nodes, edges) = graph.into_nodes_edges();
+                let edge_bound = edges.len();
+                Graph::from_slots(
+                    nodes.into_iter().map(|n| Some(n.weight)).collect(),
+                    edge_bound,
+                    edges
+                        .into_iter()
+                        .enumerate()
+                        .map(|(id, e)| (id, e.source().index(), e.target().index(), e.weight)),
+                )
+            }
+        }
+
+        /// Just like for a petgraph [`Graph`](petgraph::Graph), the indices are kept, including
+        /// the holes left by removed nodes and edges.
+        impl<T, E, Ix: IndexType> From<StableGraph<T, E, $pty, Ix>> for Graph<T, E, $ty> {
+            fn from(mut graph: StableGraph<T, E, $pty, Ix>) -> Self {
+                let node_bound = graph.node_bound();
+                let edge_ids: Vec<_> = graph.edge_indices().collect();
+                let edge_bound = edge_ids.last().map_or(0, |e| e.index() + 1);
+                let edges: Vec<_> = edge_ids
+                    .into_iter()
+                    .map(|e| {
+                        let (from, to) = graph.edge_endpoints(e).expect("a live edge");
+                        let data = graph.remove_edge(e).expect("a live edge");
+                        (e.index(), from.index(), to.index(), data)
+                    })
+                    .collect();
+                let vertices = (0..node_bound)
+                    .map(|index| graph.remove_node(NodeIndex::new(index)))
+                    .collect();
+                Graph::from_slots(vertices, edge_bound, edges.into_iter())
+            }
+        }
+    };
+}
+
+from_petgraph!(Directed, petgraph::Directed);
+from_petgraph!(Undirected, petgraph::Undirected);
+
+#[cfg(test)]
+mod tests {
+    use petgraph::{algo::dijkstra, stable_graph::StableGraph};
+
+    use crate::{graph, Graph};
+
+    #[test]
+    fn petgraph_round_trip() {
+        let (mut g, ids) =
+            graph! { "a" -> "b" : 1, "b" -> "c" : 2, "a" -> "c" : 5, "c" -> "d" : 1 };
+        g.remove_vertex(ids["b"]).unwrap();
+
+        let (pg, pids) = g.clone().into_petgraph::<u32>();
+        let (a, d) = (
+            pids.vertices[ids["a"]].unwrap(),
+            pids.vertices[ids["d"]].unwrap(),
+        );
+        assert_eq!(pids.vertices[ids["b"]], None);
+        assert_eq!((pg[a], pg[d]), ("a", "d"));
+        assert_eq!(pg[pids.edges[2].unwrap()], 5);
+        assert_eq!(dijkstra(&pg, a, Some(d), |e| *e.weight())[&d], 6);
+
+        let back = Graph::from(pg);
+        assert_eq!(back.node_count(), 3);
+        assert_eq!(
+            back.edges().map(|(_, _, &w)| w).collect::<Vec<_>>(),
+            vec![5, 1]
+        );
+    }
+
+    #[test]
+    fn stable_graph_round_trip_after_a_removal() {
+        let (mut g, ids) = graph! { "a" -> "b" : 1, "b" -> "c" : 2, "a" -> "c" : 5 };
+        g.remove_vertex(ids["a"]).unwrap();
+
+        let (sg, pids) = g.clone().into_stable_graph::<u32>();
+        let back = Graph::from(sg);
+        assert_ne!(back, g);
+        for (id, data) in g.vertices() {
+            let index = pids.vertices[id].unwrap().index();
+            assert_eq!(back[back.vertex_id(index).unwrap()], *data);
+        }
+        let bc = g.find_edges(ids["b"], ids["c"]).unwrap().next().unwrap();
+        assert_eq!(back.edge_data(pids.edges[bc].unwrap().index()), Ok(&2));
+        assert_eq!(pids.vertices[ids["a"]], None);
+    }
+
+    #[test]
+    fn graph_from_stable_graph_keeps_indices() {
+        let (mut g, ids) = graph! { "a" -> "b" : 1, "b" -> "c" : 2, "a" -> "c" : 5 };
+        let sg: StableGraph<_, _> = g.clone().into();
+        assert_eq!(Graph::from(sg), g);
+
+        g.remove_vertex(ids["b"]).unwrap();
+        let mut sg = StableGraph::<&str, i32>::from(g.clone());
+        let d = sg.add_node("d");
+        sg.add_edge(d, d, 7);
+        sg.remove_node(0.into());
+
+        let back = Graph::from(sg);
+        assert_eq!(
+            back.vertex_ids().map(|id| id.index()).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            back.edges().collect::<Vec<_>>(),
+            vec![(back.vertex_id(2).unwrap(), back.vertex_id(2).unwrap(), &7)]
+        );
+        assert_eq!(back.edge_endpoints(1).unwrap().0.index(), 2);
+    }
+}
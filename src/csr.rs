@@ -0,0 +1,567 @@
+use std::{
+    marker::PhantomData,
+    ops::{Index, Range},
+    slice,
+};
+
+use crate::{
+    visit::{EdgeRef, EdgeWeights, GraphBase, InEdges, NodeCount, NodeIndexable, Nodes, OutEdges},
+    Directed, Edge, EdgeId, EdgeRecord, EdgeType, EdgeWeight, Graph, GraphError, Policy, Vertex,
+    VertexId,
+};
+
+/// One direction of the adjacency of a [`Csr`]: the edges of all the vertices packed in parallel
+/// arrays, the ones of the vertex with index `i` being at `offsets[i]..offsets[i + 1]`. Each edge
+/// is stored with the vertex at its other end, its id and the position of its data in
+/// [`Csr::weights`], so walking a row never has to go through the edge table.
+#[derive(Debug, Clone)]
+struct Rows {
+    offsets: Vec<usize>,
+    targets: Vec<VertexId>,
+    edge_ids: Vec<EdgeId>,
+    positions: Vec<usize>,
+}
+
+impl Rows {
+    fn pack<T>(
+        vertices: &[Option<Vertex<T>>],
+        edges: &[Option<PackedEdge>],
+        list: fn(&Vertex<T>) -> &[Edge],
+    ) -> Self {
+        let mut rows = Self {
+            offsets: Vec::with_capacity(vertices.len() + 1),
+            targets: vec![],
+            edge_ids: vec![],
+            positions: vec![],
+        };
+        rows.offsets.push(0);
+        for vx in vertices {
+            for e in vx.iter().flat_map(list) {
+                let edge = edges[e.id]
+                    .as_ref()
+                    .expect("adjacency lists only hold live edges");
+                rows.targets.push(e.other_id);
+                rows.edge_ids.push(e.id);
+                rows.positions.push(edge.position);
+            }
+            rows.offsets.push(rows.targets.len());
+        }
+        rows
+    }
+
+    fn range(&self, index: usize) -> Range<usize> {
+        self.offsets[index]..self.offsets[index + 1]
+    }
+
+    fn targets(&self, index: usize) -> &[VertexId] {
+        &self.targets[self.range(index)]
+    }
+
+    fn edge_ids(&self, index: usize) -> &[EdgeId] {
+        &self.edge_ids[self.range(index)]
+    }
+
+    fn positions(&self, index: usize) -> &[usize] {
+        &self.positions[self.range(index)]
+    }
+
+    /// The row of the vertex with index `index` as adjacency list entries of a [`Graph`].
+    fn entries(&self, index: usize) -> Vec<Edge> {
+        self.range(index)
+            .map(|i| Edge {
+                id: self.edge_ids[i],
+                other_id: self.targets[i],
+            })
+            .collect()
+    }
+}
+
+/// The endpoints of an edge of a [`Csr`], and where its data is in [`Csr::weights`].
+#[derive(Debug, Clone)]
+struct PackedEdge {
+    from: VertexId,
+    to: VertexId,
+    position: usize,
+}
+
+/// An immutable snapshot of a [`Graph`] in compressed sparse row form, made by
+/// [`Graph::freeze`]. The edges of all the vertices are packed in one array per direction instead
+/// of two small vectors per vertex, and their data in a single array laid out along the outgoing
+/// edges, which makes walking the graph much friendlier to the cache. The snapshot keeps the
+/// vertex and edge ids of the graph, answers the same read-only queries, and can be turned back
+/// into a graph with [`Csr::thaw`].
+#[derive(Debug)]
+pub struct Csr<T, E = i32, Ty = Directed> {
+    /// The vertices by index, with `None` for the removed ones.
+    vertices: Vec<Option<(VertexId, T)>>,
+
+    /// The edges going out of each vertex.
+    out: Rows,
+
+    /// The edges coming into each vertex.
+    incoming: Rows,
+
+    /// The data of every edge, once, in the order the outgoing rows first list the edges. In a
+    /// directed graph that is the order of the outgoing rows themselves.
+    weights: Vec<E>,
+
+    /// The edges by id, with `None` for the removed ones.
+    edges: Vec<Option<PackedEdge>>,
+
+    policy: Policy<E>,
+    node_count: usize,
+    edge_count: usize,
+    generation: u64,
+    ty: PhantomData<Ty>,
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    /// Turns the graph into a read-only [`Csr`] snapshot, keeping all the ids valid.
+    pub fn freeze(mut self) -> Csr<T, E, Ty> {
+        let mut edges: Vec<_> = self.edges.iter().map(|_| None).collect();
+        let mut weights = Vec::with_capacity(self.edge_count);
+        for e in self.vertices.iter().flatten().flat_map(|vx| &vx.out_edges) {
+            // The edges of an undirected graph are listed by both their endpoints.
+            if let Some(edge) = self.edges[e.id].take() {
+                edges[e.id] = Some(PackedEdge {
+                    from: edge.from,
+                    to: edge.to,
+                    position: weights.len(),
+                });
+                weights.push(edge.data);
+            }
+        }
+        let out = Rows::pack(&self.vertices, &edges, |vx| &vx.out_edges);
+        let incoming = Rows::pack(&self.vertices, &edges, |vx| &vx.in_edges);
+        Csr {
+            vertices: self
+                .vertices
+                .into_iter()
+                .map(|vx| vx.map(|vx| (vx.id, vx.data)))
+                .collect(),
+            out,
+            incoming,
+            weights,
+            edges,
+            policy: self.policy,
+            node_count: self.node_count,
+            edge_count: self.edge_count,
+            generation: self.generation,
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, E: Clone, Ty> Clone for Csr<T, E, Ty> {
+    fn clone(&self) -> Self {
+        Self {
+            vertices: self.vertices.clone(),
+            out: self.out.clone(),
+            incoming: self.incoming.clone(),
+            weights: self.weights.clone(),
+            edges: self.edges.clone(),
+            policy: self.policy.clone(),
+            node_count: self.node_count,
+            edge_count: self.edge_count,
+            generation: self.generation,
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<T, E, Ty: EdgeType> Csr<T, E, Ty> {
+    /// Turns the snapshot back into a mutable graph, with the same ids as the graph it was made
+    /// from.
+    pub fn thaw(self) -> Graph<T, E, Ty> {
+        let Self {
+            vertices,
+            out,
+            incoming,
+            weights,
+            edges,
+            ..
+        } = self;
+        let mut weights: Vec<_> = weights.into_iter().map(Some).collect();
+        Graph {
+            vertices: vertices
+                .into_iter()
+                .enumerate()
+                .map(|(index, vx)| {
+                    vx.map(|(id, data)| Vertex {
+                        data,
+                        id,
+                        out_edges: out.entries(index),
+                        in_edges: incoming.entries(index),
+                    })
+                })
+                .collect(),
+            edges: edges
+                .into_iter()
+                .map(|edge| {
+                    edge.map(|e| EdgeRecord {
+                        data: weights[e.position]
+                            .take()
+                            .expect("every edge has its own data"),
+                        from: e.from,
+                        to: e.to,
+                    })
+                })
+                .collect(),
+            policy: self.policy,
+            node_count: self.node_count,
+            edge_count: self.edge_count,
+            generation: self.generation,
+            ty: PhantomData,
+        }
+    }
+
+    /// See [`Graph::is_directed`].
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// See [`Graph::get`].
+    pub fn get(&self, id: VertexId) -> Result<&T, GraphError> {
+        self.check(id)?;
+        Ok(&self.vertices[id.index].as_ref().expect("checked").1)
+    }
+
+    /// See [`Graph::node_count`].
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// See [`Graph::edge_count`].
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// See [`Graph::node_bound`].
+    pub fn node_bound(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// See [`Graph::edge_bound`].
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// See [`Graph::vertices`].
+    pub fn vertices(&self) -> impl Iterator<Item = (VertexId, &T)> + '_ {
+        self.vertices.iter().flatten().map(|(id, data)| (*id, data))
+    }
+
+    /// See [`Graph::vertex_ids`].
+    pub fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertices.iter().flatten().map(|(id, _)| *id)
+    }
+
+    /// See [`Graph::edges`].
+    pub fn edges(&self) -> impl Iterator<Item = (VertexId, VertexId, &E)> + '_ {
+        self.edges
+            .iter()
+            .flatten()
+            .map(move |e| (e.from, e.to, &self.weights[e.position]))
+    }
+
+    /// See [`Graph::edge_endpoints`].
+    pub fn edge_endpoints(&self, id: EdgeId) -> Result<(VertexId, VertexId), GraphError> {
+        self.edge(id).map(|e| (e.from, e.to))
+    }
+
+    /// See [`Graph::edge_data`].
+    pub fn edge_data(&self, id: EdgeId) -> Result<&E, GraphError> {
+        self.edge(id).map(|e| &self.weights[e.position])
+    }
+
+    /// See [`Graph::edge_weight`].
+    pub fn edge_weight(&self, id: EdgeId) -> Result<E::Weight, GraphError>
+    where
+        E: EdgeWeight,
+    {
+        self.edge_data(id).map(EdgeWeight::weight)
+    }
+
+    /// See [`Graph::find_edges`].
+    pub fn find_edges(
+        &self,
+        from_id: VertexId,
+        to_id: VertexId,
+    ) -> Result<impl Iterator<Item = EdgeId> + '_, GraphError> {
+        self.check(to_id)?;
+        self.check(from_id)?;
+        let index = from_id.index;
+        Ok(self
+            .out
+            .targets(index)
+            .iter()
+            .zip(self.out.edge_ids(index))
+            .filter(move |(&other_id, _)| other_id == to_id)
+            .map(|(_, &id)| id))
+    }
+
+    /// See [`Graph::neighbors`].
+    pub fn neighbors(&self, id: VertexId) -> Result<CsrNeighbors<'_>, GraphError> {
+        self.check(id)?;
+        Ok(CsrNeighbors {
+            iter: self.out.targets(id.index).iter(),
+        })
+    }
+
+    /// See [`Graph::out_edges`].
+    pub fn out_edges(&self, id: VertexId) -> Result<CsrAdjacentEdges<'_, E>, GraphError> {
+        self.check(id)?;
+        Ok(CsrAdjacentEdges {
+            targets: self.out.targets(id.index).iter(),
+            positions: self.out.positions(id.index).iter(),
+            weights: &self.weights,
+        })
+    }
+
+    /// See [`Graph::out_degree`].
+    pub fn out_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        self.check(id)?;
+        Ok(self.out.range(id.index).len())
+    }
+
+    /// See [`Graph::predecessors`].
+    pub fn predecessors(&self, id: VertexId) -> Result<CsrNeighbors<'_>, GraphError> {
+        self.check(id)?;
+        Ok(CsrNeighbors {
+            iter: self.incoming.targets(id.index).iter(),
+        })
+    }
+
+    /// See [`Graph::incoming_edges`].
+    pub fn incoming_edges(&self, id: VertexId) -> Result<CsrAdjacentEdges<'_, E>, GraphError> {
+        self.check(id)?;
+        Ok(CsrAdjacentEdges {
+            targets: self.incoming.targets(id.index).iter(),
+            positions: self.incoming.positions(id.index).iter(),
+            weights: &self.weights,
+        })
+    }
+
+    /// See [`Graph::in_degree`].
+    pub fn in_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        self.check(id)?;
+        Ok(self.incoming.range(id.index).len())
+    }
+
+    fn check(&self, id: VertexId) -> Result<(), GraphError> {
+        match self.vertices.get(id.index) {
+            Some(Some((vid, _))) if *vid == id => Ok(()),
+            Some(Some(_)) => Err(GraphError::StaleVertex(id)),
+            Some(None) => Err(GraphError::RemovedVertex(id)),
+            None => Err(GraphError::UnknownVertex(id)),
+        }
+    }
+
+    fn edge(&self, id: EdgeId) -> Result<&PackedEdge, GraphError> {
+        match self.edges.get(id) {
+            Some(Some(edge)) => Ok(edge),
+            Some(None) => Err(GraphError::RemovedEdge(id)),
+            None => Err(GraphError::UnknownEdge(id)),
+        }
+    }
+
+    /// The `(other_id, edge id)` pairs of the row of `id` in `rows`, panicking if `id` is not a
+    /// vertex of the snapshot.
+    fn row<'a>(
+        &self,
+        rows: &'a Rows,
+        id: VertexId,
+    ) -> impl Iterator<Item = (VertexId, EdgeId)> + 'a {
+        if let Err(err) = self.check(id) {
+            panic!("{}", err);
+        }
+        let index = id.index;
+        rows.targets(index)
+            .iter()
+            .copied()
+            .zip(rows.edge_ids(index).iter().copied())
+    }
+}
+
+impl<T, E, Ty: EdgeType> Index<VertexId> for Csr<T, E, Ty> {
+    type Output = T;
+
+    fn index(&self, id: VertexId) -> &T {
+        match self.get(id) {
+            Ok(data) => data,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// An iterator over the vertices at the other end of the edges of a vertex of a [`Csr`], see
+/// [`Csr::neighbors`] and [`Csr::predecessors`].
+pub struct CsrNeighbors<'a> {
+    iter: slice::Iter<'a, VertexId>,
+}
+
+impl Iterator for CsrNeighbors<'_> {
+    type Item = VertexId;
+
+    fn next(&mut self) -> Option<VertexId> {
+        self.iter.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CsrNeighbors<'_> {}
+
+/// An iterator over the edges of a vertex of a [`Csr`] as `(other_id, &weight)` pairs, see
+/// [`Csr::out_edges`] and [`Csr::incoming_edges`].
+pub struct CsrAdjacentEdges<'a, E> {
+    targets: slice::Iter<'a, VertexId>,
+    positions: slice::Iter<'a, usize>,
+    weights: &'a [E],
+}
+
+impl<'a, E> Iterator for CsrAdjacentEdges<'a, E> {
+    type Item = (VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = *self.positions.next()?;
+        Some((*self.targets.next()?, &self.weights[position]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.targets.size_hint()
+    }
+}
+
+impl<E> ExactSizeIterator for CsrAdjacentEdges<'_, E> {}
+
+impl<T, E, Ty: EdgeType> GraphBase for Csr<T, E, Ty> {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeCount for Csr<T, E, Ty> {
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeIndexable for Csr<T, E, Ty> {
+    fn node_bound(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn to_index(&self, id: VertexId) -> usize {
+        id.index
+    }
+
+    fn node_at(&self, index: usize) -> Option<VertexId> {
+        self.vertices.get(index)?.as_ref().map(|(id, _)| *id)
+    }
+}
+
+impl<T, E, Ty: EdgeType> Nodes for Csr<T, E, Ty> {
+    fn node_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertex_ids()
+    }
+}
+
+impl<T, E, Ty: EdgeType> OutEdges for Csr<T, E, Ty> {
+    fn out_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.row(&self.out, id)
+            .map(move |(other_id, edge)| EdgeRef {
+                id: edge,
+                source: id,
+                target: other_id,
+            })
+    }
+}
+
+impl<T, E, Ty: EdgeType> InEdges for Csr<T, E, Ty> {
+    fn in_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.row(&self.incoming, id)
+            .map(move |(other_id, edge)| EdgeRef {
+                id: edge,
+                source: other_id,
+                target: id,
+            })
+    }
+}
+
+impl<T, E: EdgeWeight, Ty: EdgeType> EdgeWeights for Csr<T, E, Ty> {
+    type Weight = E::Weight;
+
+    fn weight_of(&self, id: EdgeId) -> Option<E::Weight> {
+        self.edge_weight(id).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph, Csr, EdgeType, Graph, GraphError, OutEdges};
+
+    #[test]
+    fn freeze_and_thaw() {
+        let (mut g, ids) =
+            graph! { "a" -> "b" : 1, "b" -> "c" : 2, "a" -> "c" : 3, "c" -> "d" : 4 };
+        let (a, b, c, d) = (ids["a"], ids["b"], ids["c"], ids["d"]);
+        g.remove_vertex(b).unwrap();
+        let before = g.clone();
+
+        let csr = g.freeze();
+        assert_eq!((csr.node_count(), csr.edge_count()), (3, 2));
+        assert_eq!(csr.neighbors(a).unwrap().collect::<Vec<_>>(), vec![c]);
+        assert_eq!(csr.out_edges(c).unwrap().collect::<Vec<_>>(), vec![(d, &4)]);
+        assert_eq!(csr.predecessors(c).unwrap().collect::<Vec<_>>(), vec![a]);
+        assert_eq!(
+            csr.incoming_edges(c).unwrap().collect::<Vec<_>>(),
+            vec![(a, &3)]
+        );
+        assert_eq!(csr.in_degree(d), Ok(1));
+        assert_eq!(csr.find_edges(a, c).unwrap().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(csr.get(b), Err(GraphError::RemovedVertex(b)));
+        assert_eq!(csr.edge_weight(3), Ok(4));
+        assert_eq!(csr[d], "d");
+        assert_eq!(csr.out_neighbors(c).collect::<Vec<_>>(), vec![d]);
+
+        fn copy<T: Clone, Ty: EdgeType>(csr: &Csr<T, i32, Ty>) -> Csr<T, i32, Ty> {
+            csr.clone()
+        }
+        assert_eq!(copy(&csr).thaw(), before);
+    }
+
+    #[test]
+    fn undirected_edges_share_their_data() {
+        // The edge data does not have to be `Clone`.
+        #[derive(Debug, PartialEq)]
+        struct Label(&'static str);
+
+        let mut g = Graph::new_undirected();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let ab = g.add_weighted_edge(a, b, Label("ab")).unwrap();
+        let bb = g.add_weighted_edge(b, b, Label("bb")).unwrap();
+
+        let csr = g.freeze();
+        assert_eq!(csr.weights.len(), 2);
+        assert_eq!(
+            csr.out_edges(a).unwrap().collect::<Vec<_>>(),
+            vec![(b, &Label("ab"))]
+        );
+        assert_eq!(
+            csr.incoming_edges(b).unwrap().collect::<Vec<_>>(),
+            vec![(a, &Label("ab")), (b, &Label("bb"))]
+        );
+        assert_eq!(csr.edge_data(bb), Ok(&Label("bb")));
+
+        let g = csr.thaw();
+        assert_eq!(g.edge_data(ab), Ok(&Label("ab")));
+        assert_eq!(g.neighbors(b).unwrap().collect::<Vec<_>>(), vec![a, b]);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::visit::{Control, EdgeRef, GraphBase, NodeIndexable, OutEdges};
+
+/// The classes of the edges met by a depth-first search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// The edge led the search to a new vertex.
+    Tree,
+    /// The edge leads back to a vertex the search is still in, so it closes a cycle.
+    Back,
+    /// The edge leads to a descendant the search is already done with.
+    Forward,
+    /// The edge leads to a vertex in another branch or tree of the search.
+    Cross,
+}
+
+/// An edge met by a depth-first search, with its class.
+pub type ClassifiedEdge<N, Ed> = (EdgeRef<N, Ed>, EdgeKind);
+
+/// The events of a depth-first search reported to the visitor of [`dfs_visit`]. The times count
+/// both the discoveries and the finishes, so that every vertex gets a discovery time smaller than
+/// its finish time, and the interval of a vertex contains the intervals of its descendants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DfsEvent<N, Ed> {
+    /// The search reached a vertex, at the given time. Pruning here keeps the search from
+    /// following the edges of the vertex.
+    Discover(N, usize),
+    /// The search is looking at an edge going out of the vertex it is in. Pruning a tree edge
+    /// keeps the search from going down it.
+    Edge(EdgeRef<N, Ed>, EdgeKind),
+    /// The search is done with a vertex, at the given time.
+    Finish(N, usize),
+}
+
+/// Runs an iterative depth-first search, starting a new tree from every source that has not been
+/// reached yet; pass all the vertices of the graph to search the whole of it. Every step is
+/// reported to `visit`, which steers the search with the [`Control`] it returns. Returns
+/// [`Control::Break`] if the visitor stopped the search and [`Control::Continue`] otherwise.
+///
+/// In an undirected graph every edge is met from both its ends, so the search skips the edge it
+/// came down through and the edges to the vertices it is done with: the edges are then either
+/// tree or back edges, and each of them is reported once.
+pub fn dfs_visit<G, F>(
+    graph: &G,
+    sources: impl IntoIterator<Item = G::NodeId>,
+    mut visit: F,
+) -> Control
+where
+    G: NodeIndexable + OutEdges,
+    F: FnMut(DfsEvent<G::NodeId, G::EdgeId>) -> Control,
+{
+    let mut discovered = vec![None; graph.node_bound()];
+    let mut finished = vec![false; graph.node_bound()];
+    let mut time = 0;
+    let undirected = !graph.is_directed();
+
+    for source in sources {
+        if discovered[graph.to_index(source)].is_some() {
+            continue;
+        }
+        discovered[graph.to_index(source)] = Some(time);
+        let control = visit(DfsEvent::Discover(source, time));
+        time += 1;
+        if control == Control::Break {
+            return Control::Break;
+        }
+        // The vertices the search is in, with the edge that led to each, the edges left to
+        // follow, and whether to follow them at all.
+        let mut stack = vec![(
+            source,
+            None,
+            graph.out_edge_refs(source),
+            control == Control::Continue,
+        )];
+
+        while let Some((u, via, edges, expand)) = stack.last_mut() {
+            let (u, via) = (*u, *via);
+            let next = if *expand { edges.next() } else { None };
+            let edge = match next {
+                Some(edge) => edge,
+                None => {
+                    stack.pop();
+                    finished[graph.to_index(u)] = true;
+                    let control = visit(DfsEvent::Finish(u, time));
+                    time += 1;
+                    if control == Control::Break {
+                        return Control::Break;
+                    }
+                    continue;
+                }
+            };
+
+            let v = graph.to_index(edge.target);
+            if undirected && (via == Some(edge.id) || finished[v]) {
+                continue;
+            }
+            let kind = match discovered[v] {
+                None => EdgeKind::Tree,
+                Some(_) if !finished[v] => EdgeKind::Back,
+                Some(t) if discovered[graph.to_index(u)] < Some(t) => EdgeKind::Forward,
+                Some(_) => EdgeKind::Cross,
+            };
+            match visit(DfsEvent::Edge(edge, kind)) {
+                Control::Continue => {}
+                Control::Prune => continue,
+                Control::Break => return Control::Break,
+            }
+
+            if kind == EdgeKind::Tree {
+                discovered[v] = Some(time);
+                let control = visit(DfsEvent::Discover(edge.target, time));
+                time += 1;
+                if control == Control::Break {
+                    return Control::Break;
+                }
+                let edges = graph.out_edge_refs(edge.target);
+                stack.push((
+                    edge.target,
+                    Some(edge.id),
+                    edges,
+                    control == Control::Continue,
+                ));
+            }
+        }
+    }
+    Control::Continue
+}
+
+/// Everything a full depth-first search learned about a graph, see [`depth_first_search`].
+pub struct DfsForest<'a, G: GraphBase> {
+    graph: &'a G,
+    discovered: Vec<Option<usize>>,
+    finished: Vec<Option<usize>>,
+    parents: Vec<Option<G::NodeId>>,
+    finish_order: Vec<G::NodeId>,
+    edges: Vec<ClassifiedEdge<G::NodeId, G::EdgeId>>,
+}
+
+/// Runs a depth-first search from `sources`, see [`dfs_visit`], and records the times, the
+/// search tree, and the class of every edge.
+pub fn depth_first_search<G>(
+    graph: &G,
+    sources: impl IntoIterator<Item = G::NodeId>,
+) -> DfsForest<'_, G>
+where
+    G: NodeIndexable + OutEdges,
+{
+    let mut forest = DfsForest {
+        graph,
+        discovered: vec![None; graph.node_bound()],
+        finished: vec![None; graph.node_bound()],
+        parents: vec![None; graph.node_bound()],
+        finish_order: vec![],
+        edges: vec![],
+    };
+    dfs_visit(graph, sources, |event| {
+        match event {
+            DfsEvent::Discover(v, time) => forest.discovered[graph.to_index(v)] = Some(time),
+            DfsEvent::Edge(edge, kind) => {
+                if kind == EdgeKind::Tree {
+                    forest.parents[graph.to_index(edge.target)] = Some(edge.source);
+                }
+                forest.edges.push((edge, kind));
+            }
+            DfsEvent::Finish(v, time) => {
+                forest.finished[graph.to_index(v)] = Some(time);
+                forest.finish_order.push(v);
+            }
+        }
+        Control::Continue
+    });
+    forest
+}
+
+impl<G: NodeIndexable> DfsForest<'_, G> {
+    /// When the search reached `id`, or `None` if it did not.
+    pub fn discovery_time(&self, id: G::NodeId) -> Option<usize> {
+        self.discovered[self.graph.to_index(id)]
+    }
+
+    /// When the search was done with `id`, or `None` if it did not reach it.
+    pub fn finish_time(&self, id: G::NodeId) -> Option<usize> {
+        self.finished[self.graph.to_index(id)]
+    }
+
+    /// The vertex the search reached `id` from, or `None` for the roots of the search trees.
+    pub fn parent(&self, id: G::NodeId) -> Option<G::NodeId> {
+        self.parents[self.graph.to_index(id)]
+    }
+
+    /// The vertices in the order the search was done with them, i.e. in post-order.
+    pub fn finish_order(&self) -> &[G::NodeId] {
+        &self.finish_order
+    }
+
+    /// The edges in the order the search met them, with their class.
+    pub fn edges(&self) -> &[ClassifiedEdge<G::NodeId, G::EdgeId>] {
+        &self.edges
+    }
+
+    /// The back edges, each of which closes a cycle.
+    pub fn back_edges(&self) -> impl Iterator<Item = EdgeRef<G::NodeId, G::EdgeId>> + '_ {
+        self.edges
+            .iter()
+            .filter(|(_, kind)| *kind == EdgeKind::Back)
+            .map(|(edge, _)| *edge)
+    }
+
+    /// The cycle closed by the back edge `edge`: the path of the search tree from the target of
+    /// the edge down to its source. The edge itself leads from the last vertex back to the first.
+    pub fn cycle(&self, edge: EdgeRef<G::NodeId, G::EdgeId>) -> Vec<G::NodeId> {
+        let mut cycle = vec![edge.source];
+        let mut at = edge.source;
+        while at != edge.target {
+            at = self
+                .parent(at)
+                .expect("the target of a back edge is an ancestor of its source");
+            cycle.push(at);
+        }
+        cycle.reverse();
+        cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graph, Graph, Nodes};
+
+    #[test]
+    fn dfs_classifies_edges() {
+        let (g, ids) = graph! {
+            "a" -> "b", "b" -> "c", "c" -> "a", "a" -> "c", "d" -> "c", "d" -> "d"
+        };
+        let (a, b, c, d) = (ids["a"], ids["b"], ids["c"], ids["d"]);
+        let dfs = depth_first_search(&g, g.node_ids());
+
+        let kinds: Vec<_> = dfs
+            .edges()
+            .iter()
+            .map(|(e, kind)| (g[e.source], g[e.target], *kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("a", "b", EdgeKind::Tree),
+                ("b", "c", EdgeKind::Tree),
+                ("c", "a", EdgeKind::Back),
+                ("a", "c", EdgeKind::Forward),
+                ("d", "c", EdgeKind::Cross),
+                ("d", "d", EdgeKind::Back),
+            ]
+        );
+        assert_eq!(dfs.finish_order(), &[c, b, a, d]);
+        assert_eq!(
+            (dfs.discovery_time(a), dfs.finish_time(a)),
+            (Some(0), Some(5))
+        );
+        assert_eq!(
+            (dfs.discovery_time(c), dfs.finish_time(c)),
+            (Some(2), Some(3))
+        );
+        assert_eq!(dfs.parent(c), Some(b));
+        let cycles: Vec<_> = dfs.back_edges().map(|e| dfs.cycle(e)).collect();
+        assert_eq!(cycles, vec![vec![a, b, c], vec![d]]);
+    }
+
+    #[test]
+    fn undirected_dfs_has_tree_and_back_edges() {
+        let mut g = Graph::<_, i32, _>::new_undirected();
+        let ids: Vec<_> = ["a", "b", "c"].iter().map(|&n| g.create_node(n)).collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+            g.add_edge(ids[from], ids[to]).unwrap();
+        }
+        let dfs = depth_first_search(&g, Some(ids[0]));
+        let kinds: Vec<_> = dfs.edges().iter().map(|(_, kind)| *kind).collect();
+        assert_eq!(kinds, vec![EdgeKind::Tree, EdgeKind::Tree, EdgeKind::Back]);
+        assert_eq!(dfs.cycle(dfs.back_edges().next().unwrap()), ids);
+    }
+
+    #[test]
+    fn dfs_visitor_can_prune_and_stop() {
+        let (g, ids) = graph! { "a" -> "b", "b" -> "c", "a" -> "d", "d" -> "e" };
+        let mut seen = vec![];
+        let control = dfs_visit(&g, Some(ids["a"]), |event| match event {
+            DfsEvent::Discover(v, _) => {
+                seen.push(g[v]);
+                if g[v] == "b" {
+                    Control::Prune
+                } else if g[v] == "e" {
+                    Control::Break
+                } else {
+                    Control::Continue
+                }
+            }
+            _ => Control::Continue,
+        });
+        assert_eq!(control, Control::Break);
+        assert_eq!(seen, vec!["a", "b", "d", "e"]);
+    }
+}
@@ -0,0 +1,32 @@
+/// Marks whether the edges of a [`Graph`](crate::Graph) are directed or not. It is only ever used
+/// as a type parameter, see [`Directed`] and [`Undirected`].
+pub trait EdgeType {
+    /// The matching petgraph edge type, used when converting to and from petgraph graphs.
+    type Petgraph: petgraph::EdgeType;
+
+    fn is_directed() -> bool;
+}
+
+/// The edges have a "from" vertex and a "to" vertex and can only be traversed in that direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Directed {}
+
+/// The edges join two vertices and can be traversed either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Undirected {}
+
+impl EdgeType for Directed {
+    type Petgraph = petgraph::Directed;
+
+    fn is_directed() -> bool {
+        true
+    }
+}
+
+impl EdgeType for Undirected {
+    type Petgraph = petgraph::Undirected;
+
+    fn is_directed() -> bool {
+        false
+    }
+}
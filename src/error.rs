@@ -0,0 +1,68 @@
+use std::{error::Error, fmt};
+
+use crate::{EdgeId, VertexId};
+
+/// The error returned by the fallible operations on a [`Graph`](crate::Graph).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GraphError {
+    /// The vertex id does not refer to any vertex of the graph.
+    UnknownVertex(VertexId),
+    /// The vertex id belonged to a vertex that has since been removed from the graph.
+    RemovedVertex(VertexId),
+    /// The vertex handle is out of date: the vertex it referred to has been moved or removed by
+    /// a vacuum and the slot now holds another vertex.
+    StaleVertex(VertexId),
+    /// The vertex exists in the graph but is hidden by the view it was looked up in.
+    HiddenVertex(VertexId),
+    /// The edge id does not refer to any edge of the graph.
+    UnknownEdge(EdgeId),
+    /// The edge id belonged to an edge that has since been removed from the graph.
+    RemovedEdge(EdgeId),
+    /// The graph does not allow parallel edges and the vertices are already joined by this edge.
+    DuplicateEdge(EdgeId),
+    /// The graph does not allow an edge from this vertex to itself.
+    SelfLoop(VertexId),
+    /// No vertex goes by this name (or key), shown in its `Debug` format.
+    UnknownKey(String),
+    /// Another vertex already goes by this name (or key), shown in its `Debug` format.
+    DuplicateKey(String),
+    /// A vertex made for a key turned out to have another key. Both keys are shown in their
+    /// `Debug` format.
+    KeyMismatch { expected: String, found: String },
+    /// The side table missed a vacuum of its graph, so it cannot be remapped any more.
+    StaleMap,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::UnknownVertex(id) => write!(f, "unknown vertex {}", id),
+            GraphError::RemovedVertex(id) => write!(f, "vertex {} has been removed", id),
+            GraphError::StaleVertex(id) => write!(f, "vertex handle {} is stale", id),
+            GraphError::HiddenVertex(id) => write!(f, "vertex {} is hidden by the view", id),
+            GraphError::UnknownEdge(id) => write!(f, "unknown edge {}", id),
+            GraphError::RemovedEdge(id) => write!(f, "edge {} has been removed", id),
+            GraphError::DuplicateEdge(id) => {
+                write!(
+                    f,
+                    "parallel edges are not allowed, edge {} already exists",
+                    id
+                )
+            }
+            GraphError::SelfLoop(id) => write!(f, "self loops are not allowed at vertex {}", id),
+            GraphError::UnknownKey(key) => write!(f, "no vertex is keyed {}", key),
+            GraphError::DuplicateKey(key) => write!(f, "a vertex keyed {} already exists", key),
+            GraphError::KeyMismatch { expected, found } => {
+                write!(
+                    f,
+                    "the new vertex is keyed {} instead of {}",
+                    found, expected
+                )
+            }
+            GraphError::StaleMap => f.write_str("the map missed a vacuum of its graph"),
+        }
+    }
+}
+
+impl Error for GraphError {}
@@ -0,0 +1,94 @@
+use std::{iter::Flatten, slice};
+
+use crate::{Edge, EdgeRecord, Vertex, VertexId};
+
+/// An iterator over the live vertices of a graph as `(id, &data)` pairs, see
+/// [`Graph::vertices`](crate::Graph::vertices).
+pub struct Vertices<'a, T> {
+    pub(crate) iter: Flatten<slice::Iter<'a, Option<Vertex<T>>>>,
+}
+
+impl<'a, T> Iterator for Vertices<'a, T> {
+    type Item = (VertexId, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|vx| (vx.id, &vx.data))
+    }
+}
+
+/// An iterator over the ids of the live vertices of a graph, see
+/// [`Graph::vertex_ids`](crate::Graph::vertex_ids).
+pub struct VertexIds<'a, T> {
+    pub(crate) iter: Vertices<'a, T>,
+}
+
+impl<'a, T> Iterator for VertexIds<'a, T> {
+    type Item = VertexId;
+
+    fn next(&mut self) -> Option<VertexId> {
+        self.iter.next().map(|(id, _)| id)
+    }
+}
+
+/// An iterator over the live edges of a graph as `(from_id, to_id, &weight)` triples, see
+/// [`Graph::edges`](crate::Graph::edges).
+pub struct Edges<'a, E> {
+    pub(crate) iter: Flatten<slice::Iter<'a, Option<EdgeRecord<E>>>>,
+}
+
+impl<'a, E> Iterator for Edges<'a, E> {
+    type Item = (VertexId, VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|edge| (edge.from, edge.to, &edge.data))
+    }
+}
+
+/// An iterator over the vertices at the other end of the edges of a vertex, see
+/// [`Graph::neighbors`](crate::Graph::neighbors) and
+/// [`Graph::predecessors`](crate::Graph::predecessors).
+pub struct Neighbors<'a> {
+    pub(crate) iter: slice::Iter<'a, Edge>,
+}
+
+impl<'a> Iterator for Neighbors<'a> {
+    type Item = VertexId;
+
+    fn next(&mut self) -> Option<VertexId> {
+        self.iter.next().map(|e| e.other_id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Neighbors<'_> {}
+
+/// An iterator over the edges of a vertex as `(other_id, &weight)` pairs, see
+/// [`Graph::out_edges`](crate::Graph::out_edges) and
+/// [`Graph::incoming_edges`](crate::Graph::incoming_edges).
+pub struct AdjacentEdges<'a, E> {
+    pub(crate) iter: slice::Iter<'a, Edge>,
+    pub(crate) edges: &'a [Option<EdgeRecord<E>>],
+}
+
+impl<'a, E> Iterator for AdjacentEdges<'a, E> {
+    type Item = (VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let e = self.iter.next()?;
+        let edge = self.edges[e.id]
+            .as_ref()
+            .expect("adjacency lists only hold live edges");
+        Some((e.other_id, &edge.data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<E> ExactSizeIterator for AdjacentEdges<'_, E> {}
@@ -0,0 +1,287 @@
+use std::{borrow::Borrow, collections::HashMap, fmt::Debug, hash::Hash, ops::Deref};
+
+use crate::{Directed, EdgeId, EdgeType, EdgeWeight, Graph, GraphError, Remapping, VertexId};
+
+/// Computes the key of a vertex out of its data.
+type KeyFn<T, K> = Box<dyn Fn(&T) -> K>;
+
+/// A [`Graph`] that keeps an index from a key of the vertex data to the [`VertexId`], so that the
+/// vertices can be looked up by their data. No two vertices may have the same key.
+///
+/// The index is kept up to date by routing every mutation of the vertices through the
+/// `KeyedGraph`; reading the graph is done through `Deref`.
+pub struct KeyedGraph<K, T, E = i32, Ty = Directed> {
+    graph: Graph<T, E, Ty>,
+    index: HashMap<K, VertexId>,
+    key_of: KeyFn<T, K>,
+}
+
+impl<T, E> KeyedGraph<T, T, E>
+where
+    T: Hash + Eq + Clone + Debug + 'static,
+{
+    /// Creates an empty directed graph whose vertices are keyed by their whole data.
+    pub fn new() -> Self {
+        Self::with_key_fn(T::clone)
+    }
+}
+
+impl<T, E> Default for KeyedGraph<T, T, E>
+where
+    T: Hash + Eq + Clone + Debug + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T, E> KeyedGraph<K, T, E>
+where
+    K: Hash + Eq + Debug,
+{
+    /// Creates an empty directed graph whose vertices are keyed by `key_of(&data)`.
+    pub fn with_key_fn<F: Fn(&T) -> K + 'static>(key_of: F) -> Self {
+        Self {
+            graph: Graph::new(),
+            index: HashMap::new(),
+            key_of: Box::new(key_of),
+        }
+    }
+}
+
+impl<K, T, E, Ty> KeyedGraph<K, T, E, Ty>
+where
+    K: Hash + Eq + Debug,
+    Ty: EdgeType,
+{
+    /// Indexes the vertices of an existing graph by `key_of(&data)`. Fails if two of the vertices
+    /// have the same key.
+    pub fn from_graph<F: Fn(&T) -> K + 'static>(
+        graph: Graph<T, E, Ty>,
+        key_of: F,
+    ) -> Result<Self, GraphError> {
+        let mut index = HashMap::with_capacity(graph.node_count());
+        for (id, data) in graph.vertices() {
+            let key = key_of(data);
+            if index.contains_key(&key) {
+                return Err(GraphError::DuplicateKey(format!("{:?}", key)));
+            }
+            index.insert(key, id);
+        }
+        Ok(Self {
+            graph,
+            index,
+            key_of: Box::new(key_of),
+        })
+    }
+
+    /// Gives up the index and returns the plain graph.
+    pub fn into_graph(self) -> Graph<T, E, Ty> {
+        self.graph
+    }
+
+    /// Returns the id of the vertex with the key `key`.
+    pub fn find_vertex<Q>(&self, key: &Q) -> Option<VertexId>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(key).copied()
+    }
+
+    /// Creates a vertex storing `data`, unless there already is a vertex with the same key.
+    pub fn create_node(&mut self, data: T) -> Result<VertexId, GraphError> {
+        let key = (self.key_of)(&data);
+        if self.index.contains_key(&key) {
+            return Err(GraphError::DuplicateKey(format!("{:?}", key)));
+        }
+        let id = self.graph.create_node(data);
+        self.index.insert(key, id);
+        Ok(id)
+    }
+
+    /// Returns the id of the vertex with the key `key`, creating a vertex storing `make()` if
+    /// there is none. `make` is only called when the vertex has to be created, and fails, leaving
+    /// the graph alone, if the data it returns does not have the key `key`.
+    pub fn get_or_create_with<Q, F>(&mut self, key: &Q, make: F) -> Result<VertexId, GraphError>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + Debug + ?Sized,
+        F: FnOnce() -> T,
+    {
+        if let Some(&id) = self.index.get(key) {
+            return Ok(id);
+        }
+        let data = make();
+        let found = (self.key_of)(&data);
+        if found.borrow() != key {
+            return Err(GraphError::KeyMismatch {
+                expected: format!("{:?}", key),
+                found: format!("{:?}", found),
+            });
+        }
+        let id = self.graph.create_node(data);
+        self.index.insert(found, id);
+        Ok(id)
+    }
+
+    /// Replaces the data of the vertex `id` and returns the old data. Fails, leaving the vertex
+    /// alone, if the new key belongs to another vertex.
+    pub fn replace(&mut self, id: VertexId, data: T) -> Result<T, GraphError> {
+        let old_key = (self.key_of)(self.graph.get(id)?);
+        let key = (self.key_of)(&data);
+        match self.index.get(&key) {
+            Some(&other) if other != id => {
+                return Err(GraphError::DuplicateKey(format!("{:?}", key)));
+            }
+            _ => {}
+        }
+        self.index.remove(&old_key);
+        self.index.insert(key, id);
+        Ok(std::mem::replace(self.graph.get_mut_data(id)?, data))
+    }
+
+    /// Removes the vertex `id` from the graph and from the index, see [`Graph::remove_vertex`].
+    pub fn remove_vertex(&mut self, id: VertexId) -> Result<T, GraphError> {
+        let data = self.graph.remove_vertex(id)?;
+        self.index.remove(&(self.key_of)(&data));
+        Ok(data)
+    }
+
+    /// Vacuums the graph and updates the index to the new ids, see [`Graph::vacuum`].
+    pub fn vacuum(&mut self) -> Remapping {
+        let remap = self.graph.vacuum();
+        for id in self.index.values_mut() {
+            *id = remap
+                .vertex(*id)
+                .expect("the index only holds live vertices");
+        }
+        remap
+    }
+
+    /// See [`Graph::add_weighted_edge`].
+    pub fn add_weighted_edge(
+        &mut self,
+        from_id: VertexId,
+        to_id: VertexId,
+        data: E,
+    ) -> Result<EdgeId, GraphError> {
+        self.graph.add_weighted_edge(from_id, to_id, data)
+    }
+
+    /// See [`Graph::add_edge`].
+    pub fn add_edge(&mut self, from_id: VertexId, to_id: VertexId) -> Result<EdgeId, GraphError>
+    where
+        E: Default,
+    {
+        self.graph.add_edge(from_id, to_id)
+    }
+
+    /// See [`Graph::remove_edge`].
+    pub fn remove_edge(&mut self, id: EdgeId) -> Result<E, GraphError> {
+        self.graph.remove_edge(id)
+    }
+
+    /// See [`Graph::edge_data_mut`].
+    pub fn edge_data_mut(&mut self, id: EdgeId) -> Result<&mut E, GraphError> {
+        self.graph.edge_data_mut(id)
+    }
+
+    /// See [`Graph::set_weight`].
+    pub fn set_weight(&mut self, id: EdgeId, weight: E::Weight) -> Result<E::Weight, GraphError>
+    where
+        E: EdgeWeight,
+    {
+        self.graph.set_weight(id, weight)
+    }
+}
+
+impl<T, E, Ty> KeyedGraph<T, T, E, Ty>
+where
+    T: Hash + Eq + Debug,
+    Ty: EdgeType,
+{
+    /// Returns the id of the vertex with the same key as `data`, creating a vertex storing `data`
+    /// if there is none. When the vertex already exists `data` is dropped. This is the shorthand
+    /// of [`KeyedGraph::get_or_create_with`] for the graphs whose vertices are their own keys.
+    pub fn get_or_create(&mut self, data: T) -> VertexId {
+        let key = (self.key_of)(&data);
+        match self.index.get(&key) {
+            Some(&id) => id,
+            None => {
+                let id = self.graph.create_node(data);
+                self.index.insert(key, id);
+                id
+            }
+        }
+    }
+}
+
+impl<K, T, E, Ty> Deref for KeyedGraph<K, T, E, Ty> {
+    type Target = Graph<T, E, Ty>;
+
+    fn deref(&self) -> &Graph<T, E, Ty> {
+        &self.graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Graph, GraphError, KeyedGraph};
+
+    #[test]
+    fn vertices_are_found_by_key() {
+        let mut g = KeyedGraph::<String, String>::new();
+        let api = g.get_or_create("api".to_string());
+        let db = g.create_node("db".to_string()).unwrap();
+        assert_eq!(g.get_or_create("api".to_string()), api);
+        assert_eq!(
+            g.create_node("db".to_string()),
+            Err(GraphError::DuplicateKey("\"db\"".to_string()))
+        );
+        g.add_edge(api, db).unwrap();
+        assert_eq!(g.find_vertex("db"), Some(db));
+        assert_eq!(g.node_count(), 2);
+
+        assert_eq!(
+            g.replace(api, "db".to_string()),
+            Err(GraphError::DuplicateKey("\"db\"".to_string()))
+        );
+        assert_eq!(g.replace(api, "gateway".to_string()), Ok("api".to_string()));
+        assert_eq!(g.find_vertex("api"), None);
+        assert_eq!(g.find_vertex("gateway"), Some(api));
+
+        g.remove_vertex(api).unwrap();
+        assert_eq!(g.find_vertex("gateway"), None);
+        g.vacuum();
+        let db = g.find_vertex("db").unwrap();
+        assert_eq!(g[db], "db");
+    }
+
+    #[test]
+    fn vertices_are_keyed_by_a_closure() {
+        let mut graph: Graph<_, i32> = Graph::new();
+        graph.create_node((80, "http"));
+        graph.create_node((443, "https"));
+        let mut g = KeyedGraph::from_graph(graph, |&(port, _): &(u16, &str)| port).unwrap();
+        let https = g.find_vertex(&443).unwrap();
+        assert_eq!(g[https].1, "https");
+
+        assert_eq!(g.get_or_create_with(&443, || unreachable!()), Ok(https));
+        let ssh = g.get_or_create_with(&22, || (22, "ssh")).unwrap();
+        assert_eq!(g[ssh], (22, "ssh"));
+        assert_eq!(
+            g.get_or_create_with(&8080, || (8000, "alt")),
+            Err(GraphError::KeyMismatch {
+                expected: "8080".to_string(),
+                found: "8000".to_string()
+            })
+        );
+        assert_eq!(g.node_count(), 3);
+
+        let mut graph: Graph<_, i32> = Graph::new();
+        graph.create_node((80, "http"));
+        graph.create_node((80, "www"));
+        assert!(KeyedGraph::from_graph(graph, |&(port, _): &(u16, &str)| port).is_err());
+    }
+}
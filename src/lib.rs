@@ -1,25 +1,126 @@
-use std::{fs::File, io::Write, path::Path, vec};
+use std::{
+    fmt,
+    fs::File,
+    io::Write,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+    path::Path,
+    sync::Arc,
+    vec,
+};
 
 use petgraph::dot::{Config, Dot};
-use petgraph::{Directed, Graph as PG};
 
-type VertexId = usize;
-type Weight = i32;
+mod bfs;
+mod builder;
+mod convert;
+mod csr;
+mod dfs;
+mod edge_type;
+mod error;
+mod iter;
+mod keyed;
+mod maps;
+mod matrix;
+mod policy;
+mod scc;
+#[cfg(feature = "serde")]
+mod serialize;
+mod toposort;
+mod view;
+mod visit;
+mod weight;
 
-/// All edges are directed.
-#[derive(Debug)]
+pub use bfs::{bfs_visit, Bfs, BfsEvent, BfsStep};
+pub use builder::{GraphBuilder, NamedGraph};
+pub use convert::PetgraphIds;
+pub use csr::{Csr, CsrAdjacentEdges, CsrNeighbors};
+pub use dfs::{depth_first_search, dfs_visit, ClassifiedEdge, DfsEvent, DfsForest, EdgeKind};
+pub use edge_type::{Directed, EdgeType, Undirected};
+pub use error::GraphError;
+pub use iter::{AdjacentEdges, Edges, Neighbors, VertexIds, Vertices};
+pub use keyed::KeyedGraph;
+pub use maps::{EdgeMap, VertexMap};
+pub use matrix::AdjacencyMatrix;
+pub use policy::{Combiner, ParallelEdges, Policy};
+pub use scc::{kosaraju_scc, tarjan_scc, Components};
+pub use toposort::{toposort, toposort_by_key, toposort_dfs, Cycle};
+pub use view::{
+    EdgeFilter, Filter, FilteredAdjacentEdges, FilteredEdges, FilteredGraph, FilteredNeighbors,
+    FilteredVertices, Reversed, ReversedEdges, VertexFilter, ViewFilter,
+};
+pub use visit::{
+    Control, EdgeRef, EdgeWeights, GraphBase, InEdges, NodeCount, NodeIndexable, Nodes, OutEdges,
+};
+pub use weight::{EdgeWeight, Total, Weight};
+
+/// A handle to a vertex of a [`Graph`]. Besides the index of the vertex in the graph, the handle
+/// carries the generation the vertex was given, so that a handle that outlived its vertex is
+/// reported as [`GraphError::StaleVertex`] instead of silently referring to whichever vertex was
+/// moved into its slot by a [`Graph::vacuum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexId {
+    index: usize,
+    generation: u64,
+}
+
+impl VertexId {
+    /// The index of the vertex in the graph. The indices of the live vertices are below
+    /// [`Graph::node_bound`], so they can be used to address dense per-vertex arrays.
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    /// The generation of the vertex, i.e. the number of vacuums the graph had gone through when
+    /// the vertex was created or last moved.
+    pub fn generation(self) -> u64 {
+        self.generation
+    }
+}
+
+impl fmt::Display for VertexId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.index, self.generation)
+    }
+}
+
+pub type EdgeId = usize;
+
+/// An edge has a "from" vertex and a "to" vertex; for undirected graphs that is just the order in
+/// which the endpoints were given. The edges are stored once in the edge pool of the graph and
+/// the vertices refer to them by their [`EdgeId`].
+#[derive(Debug, Clone)]
+struct EdgeRecord<E> {
+    /// The data stored on the edge. For a plain weighted graph this is just the weight, which is
+    /// 0 for un-weighted edges. Weights can be negative if the type allows it.
+    data: E,
+
+    /// The vertex the edge starts at.
+    from: VertexId,
+
+    /// The vertex the edge ends at.
+    to: VertexId,
+}
+
+/// An entry in the adjacency lists of a vertex.
+#[derive(Debug, Clone)]
 struct Edge {
-    /// The weight of the edge. The weight is 0 for un-weighted edges. Weights can be negative.
-    wt: Weight,
+    /// The id of the edge in the edge pool of the graph. Parallel edges share the `other_id` but
+    /// never the `id`.
+    id: EdgeId,
 
     /// All edges are directed and they have a "from" vertex and a "to" vertex. An edge is owned by
     /// a vertex and the owning vertex is the implicit vertex. The "id" field stores the id of the
     /// other (non-implicit vertex), which happens to be the id of the "to" vertex for the `out`
-    /// edges and the ids of the "from" vertex for the "in_edges".
+    /// edges and the ids of the "from" vertex for the "in_edges". In an undirected graph an edge is
+    /// listed in both the `out_edges` and the `in_edges` of both of its endpoints, so that the
+    /// neighbors are symmetric.
     other_id: VertexId,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Vertex<T> {
     /// The the data item the node stores.
     data: T,
@@ -44,88 +145,680 @@ impl<T> Vertex<T> {
         }
     }
 
-    fn add_out(&mut self, id: VertexId, wt: Weight) {
-        self.out_edges.push(Edge { wt, other_id: id });
+    fn add_out(&mut self, edge: EdgeId, id: VertexId) {
+        self.out_edges.push(Edge {
+            id: edge,
+            other_id: id,
+        });
     }
 
-    fn add_in(&mut self, id: VertexId, wt: Weight) {
-        self.in_edges.push(Edge { wt, other_id: id });
+    fn add_in(&mut self, edge: EdgeId, id: VertexId) {
+        self.in_edges.push(Edge {
+            id: edge,
+            other_id: id,
+        });
     }
 }
 
-pub struct Graph<T> {
+/// A graph storing data of type `T` in its vertices and data of type `E` on its edges. The edge
+/// data is usually just a [`Weight`], but it can be any type that can tell its weight by
+/// implementing [`EdgeWeight`], for example a `(weight, label)` pair. The edges are directed
+/// unless `Ty` is [`Undirected`].
+pub struct Graph<T, E = i32, Ty = Directed> {
     /// This is the pool of all the vertices. A vertex is referenced by the index in this vector.
     /// As new nodes are added they get added here. Whn nodes are deleted, then they are removed
-    /// from here. When nodes are removed, it leaves an unused index which should be vacuumed
-    /// away.
-    vertices: Vec<Vertex<T>>,
+    /// from here. When nodes are removed, it leaves an unused index (a `None` tombstone) which
+    /// should be vacuumed away by [`Graph::vacuum`].
+    vertices: Vec<Option<Vertex<T>>>,
+
+    /// This is the pool of all the edges, referenced by their [`EdgeId`]. Just like the vertices,
+    /// removed edges leave a `None` tombstone behind until the next [`Graph::vacuum`].
+    edges: Vec<Option<EdgeRecord<E>>>,
+
+    /// The rules for parallel edges and self loops, checked on every edge insertion.
+    policy: Policy<E>,
+
+    /// The number of live vertices, i.e. `vertices` without the tombstones.
+    node_count: usize,
+
+    /// The number of live edges, i.e. `edges` without the tombstones.
+    edge_count: usize,
+
+    /// The generation handed out to new vertices. Every vacuum bumps it, so that the vertices it
+    /// moves around get handles that cannot be mistaken for the ones handed out before.
+    generation: u64,
+
+    ty: PhantomData<Ty>,
+}
+
+/// The old-id -> new-id table produced by [`Graph::vacuum`]. Callers that keep their own side
+/// tables keyed by [`VertexId`] or [`EdgeId`] should run them through this table after a vacuum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remapping {
+    /// The `(old, new)` ids, indexed by the old index. `None` for the indices that belonged to
+    /// removed vertices.
+    vertices: Vec<Option<(VertexId, VertexId)>>,
+
+    /// Indexed by the old id. `None` for the ids that belonged to removed edges.
+    edges: Vec<Option<EdgeId>>,
+
+    /// The generation of the graph after the vacuum.
+    generation: u64,
+}
+
+impl Remapping {
+    /// Returns the new id of the vertex that had the id `old` before the vacuum, or `None` if
+    /// `old` was not the id of a live vertex.
+    pub fn vertex(&self, old: VertexId) -> Option<VertexId> {
+        match self.vertices.get(old.index) {
+            Some(&Some((was, new))) if was == old => Some(new),
+            _ => None,
+        }
+    }
+
+    /// The whole remapping table as `(old, new)` pairs, in the order of the old ids.
+    pub fn vertices(&self) -> impl Iterator<Item = (VertexId, VertexId)> + '_ {
+        self.vertices.iter().flatten().copied()
+    }
+
+    /// Returns the new id of the edge that had the id `old` before the vacuum, or `None` if `old`
+    /// was not the id of a live edge.
+    pub fn edge(&self, old: EdgeId) -> Option<EdgeId> {
+        self.edges.get(old).copied().flatten()
+    }
+
+    /// The whole edge remapping table, indexed by the old id.
+    pub fn edges(&self) -> &[Option<EdgeId>] {
+        &self.edges
+    }
 }
 
-impl<T> Graph<T> {
+impl<T, E, Ty: EdgeType> Default for Graph<T, E, Ty> {
+    fn default() -> Self {
+        Self::with_policy(Policy::default())
+    }
+}
+
+impl<T, E> Graph<T, E> {
+    /// Creates an empty directed graph.
     pub fn new() -> Self {
-        Self { vertices: vec![] }
+        Self::default()
     }
+}
 
-    /// A create node creates a node with the provided data and returns the id of the node. This id
-    /// should be used to retrieve the node.
-    fn create_node(&mut self, data: T) -> VertexId {
-        let next_idx = self.vertices.len() as VertexId;
-        let vx = Vertex::new(next_idx, data);
-        self.vertices.push(vx);
-        next_idx
+impl<T, E> Graph<T, E, Undirected> {
+    /// Creates an empty undirected graph.
+    pub fn new_undirected() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    /// Creates an empty graph that enforces `policy` on every edge insertion.
+    pub fn with_policy(policy: Policy<E>) -> Self {
+        Self {
+            vertices: vec![],
+            edges: vec![],
+            policy,
+            node_count: 0,
+            edge_count: 0,
+            generation: 0,
+            ty: PhantomData,
+        }
+    }
+
+    /// The policy this graph enforces.
+    pub fn policy(&self) -> &Policy<E> {
+        &self.policy
     }
 
-    fn get_mut_data(&mut self, id: VertexId) -> Option<&mut T> {
-        self.vertices.get_mut(id as usize).map(|v| &mut v.data)
+    /// Whether the edges of this graph are directed.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
     }
 
-    fn add_weighted_edge(&mut self, from_id: VertexId, to_id: VertexId, weight: Weight) {
-        self.vertices
-            .get_mut(from_id as usize)
-            .map(|vx| vx.add_out(to_id, weight));
+    /// A create node creates a node with the provided data and returns the id of the node. This id
+    /// should be used to retrieve the node.
+    pub fn create_node(&mut self, data: T) -> VertexId {
+        let id = VertexId {
+            index: self.vertices.len(),
+            generation: self.generation,
+        };
+        self.vertices.push(Some(Vertex::new(id, data)));
+        self.node_count += 1;
+        id
     }
 
-    fn add_edge(&mut self, from_id: VertexId, to_id: VertexId) {
-        self.add_weighted_edge(from_id, to_id, 0)
+    /// Removes the vertex `id` along with all the edges coming into and going out of it, and
+    /// returns its data. The ids of the remaining vertices are unaffected; the slot of the removed
+    /// vertex stays unused until the next [`Graph::vacuum`].
+    pub fn remove_vertex(&mut self, id: VertexId) -> Result<T, GraphError> {
+        self.vertex(id)?;
+        let vx = self.vertices[id.index].take().expect("checked above");
+        for e in vx.out_edges.iter().chain(vx.in_edges.iter()) {
+            // Self loops and undirected edges show up in both the lists, so they may already be
+            // gone.
+            if let Some(edge) = self.edges[e.id].take() {
+                self.unlink(e.id, edge.from, edge.to);
+                self.edge_count -= 1;
+            }
+        }
+        self.node_count -= 1;
+        Ok(vx.data)
     }
 
-    fn draw(&self, filename: &str) {
-        let edges = self
+    /// Compacts the pools of vertices and edges by dropping the slots left behind by removed
+    /// vertices and edges. The survivors keep their relative order but the ones that had to move
+    /// get new ids, and the returned [`Remapping`] tells what the old id of every vertex and edge
+    /// has turned into. The old handles of the moved vertices are stale from now on.
+    pub fn vacuum(&mut self) -> Remapping {
+        self.generation += 1;
+        let generation = self.generation;
+        let indices = compact(&mut self.vertices);
+        let edges = compact(&mut self.edges);
+        let vertices = self
             .vertices
             .iter()
-            .flat_map(|vx| {
-                vx.out_edges
-                    .iter()
-                    .map(|out| (vx.id, out.other_id))
-                    .collect::<Vec<(VertexId, VertexId)>>()
+            .flatten()
+            .map(|vx| vx.id)
+            .zip(indices.iter().flatten())
+            .map(|(old, &index)| {
+                let new = if index == old.index {
+                    old
+                } else {
+                    VertexId { index, generation }
+                };
+                (old, new)
             })
-            .collect::<Vec<(VertexId, VertexId)>>();
-        println!("{:?}", edges);
+            .collect::<Vec<_>>();
+        let new_id = |old: VertexId| vertices[indices[old.index].expect("live vertex")].1;
 
-        let mut graph =
-            PG::<_, Weight, Directed, VertexId>::with_capacity(self.vertices.len(), edges.len());
-        self.vertices.iter().for_each(|vx| {
-            graph.add_node(vx.id);
+        for vx in self.vertices.iter_mut().flatten() {
+            vx.id = new_id(vx.id);
+            for e in vx.out_edges.iter_mut().chain(vx.in_edges.iter_mut()) {
+                e.id = edges[e.id].expect("adjacency lists only hold live edges");
+                e.other_id = new_id(e.other_id);
+            }
+        }
+        for edge in self.edges.iter_mut().flatten() {
+            edge.from = new_id(edge.from);
+            edge.to = new_id(edge.to);
+        }
+
+        let mut table = vec![None; indices.len()];
+        for (old, new) in vertices {
+            table[old.index] = Some((old, new));
+        }
+        Remapping {
+            vertices: table,
+            edges,
+            generation,
+        }
+    }
+
+    /// Returns the handle of the live vertex at `index`, if there is one.
+    pub fn vertex_id(&self, index: usize) -> Option<VertexId> {
+        self.vertices.get(index)?.as_ref().map(|vx| vx.id)
+    }
+
+    /// One more than the largest index of a vertex, live or removed. Dense per-vertex arrays
+    /// indexed by [`VertexId::index`] need this many slots.
+    pub fn node_bound(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// One more than the largest edge id, live or removed.
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The number of vacuums this graph has gone through. Side tables remember the generation
+    /// they were made for, to tell when they need to be remapped.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Creates a [`VertexMap`] for this graph, holding `default` for every vertex.
+    pub fn vertex_map<V: Clone>(&self, default: V) -> VertexMap<V> {
+        VertexMap::new(self, default)
+    }
+
+    /// Creates an [`EdgeMap`] for this graph, holding `default` for every edge.
+    pub fn edge_map<V: Clone>(&self, default: V) -> EdgeMap<V> {
+        EdgeMap::new(self, default)
+    }
+
+    /// Returns the data stored in the vertex `id`.
+    pub fn get(&self, id: VertexId) -> Result<&T, GraphError> {
+        self.vertex(id).map(|v| &v.data)
+    }
+
+    /// Returns a mutable reference to the data stored in the vertex `id`.
+    pub fn get_mut_data(&mut self, id: VertexId) -> Result<&mut T, GraphError> {
+        self.vertex_mut(id).map(|v| &mut v.data)
+    }
+
+    /// Adds a directed edge from `from_id` to `to_id` carrying the given weight (or any other edge
+    /// data) and returns the id of the new edge. Both the endpoints must be vertices of this
+    /// graph, otherwise the graph is left untouched and an error is returned. The edge is
+    /// recorded in the `out_edges` of `from_id` as well as in the `in_edges` of `to_id`, and the
+    /// other way around too if the graph is undirected.
+    ///
+    /// The [`Policy`] of the graph may reject the edge, or merge it into an existing parallel
+    /// edge, in which case the id of that existing edge is returned.
+    pub fn add_weighted_edge(
+        &mut self,
+        from_id: VertexId,
+        to_id: VertexId,
+        data: E,
+    ) -> Result<EdgeId, GraphError> {
+        self.vertex(to_id)?;
+        self.vertex(from_id)?;
+        if from_id == to_id && !self.policy.allows_self_loops() {
+            return Err(GraphError::SelfLoop(from_id));
+        }
+        let existing = self.find_edges(from_id, to_id)?.next();
+        if let Some(existing) = existing {
+            match self.policy.parallel_edges_policy() {
+                ParallelEdges::Allow => {}
+                ParallelEdges::Deny => return Err(GraphError::DuplicateEdge(existing)),
+                ParallelEdges::Merge(combine) => {
+                    let combine = Arc::clone(combine);
+                    combine(&mut self.edge_mut(existing)?.data, data);
+                    return Ok(existing);
+                }
+            }
+        }
+
+        let id = self.edges.len() as EdgeId;
+        self.edges.push(None);
+        self.link(id, from_id, to_id, data);
+        Ok(id)
+    }
+
+    /// Adds an un-weighted edge, i.e. an edge with the default edge data (weight 0), from
+    /// `from_id` to `to_id`.
+    pub fn add_edge(&mut self, from_id: VertexId, to_id: VertexId) -> Result<EdgeId, GraphError>
+    where
+        E: Default,
+    {
+        self.add_weighted_edge(from_id, to_id, E::default())
+    }
+
+    /// Removes the edge `id` from the graph and returns its data. Parallel edges between the same
+    /// pair of vertices are left alone.
+    pub fn remove_edge(&mut self, id: EdgeId) -> Result<E, GraphError> {
+        self.edge(id)?;
+        let edge = self.edges[id].take().expect("checked above");
+        self.unlink(id, edge.from, edge.to);
+        self.edge_count -= 1;
+        Ok(edge.data)
+    }
+
+    /// Returns the `(from_id, to_id)` pair of the edge `id`.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Result<(VertexId, VertexId), GraphError> {
+        self.edge(id).map(|e| (e.from, e.to))
+    }
+
+    /// Returns the data stored on the edge `id`.
+    pub fn edge_data(&self, id: EdgeId) -> Result<&E, GraphError> {
+        self.edge(id).map(|e| &e.data)
+    }
+
+    /// Returns a mutable reference to the data stored on the edge `id`.
+    pub fn edge_data_mut(&mut self, id: EdgeId) -> Result<&mut E, GraphError> {
+        self.edge_mut(id).map(|e| &mut e.data)
+    }
+
+    /// Returns the weight of the edge `id`.
+    pub fn edge_weight(&self, id: EdgeId) -> Result<E::Weight, GraphError>
+    where
+        E: EdgeWeight,
+    {
+        self.edge(id).map(|e| e.data.weight())
+    }
+
+    /// Replaces the weight of the edge `id` and returns the previous one. The rest of the edge
+    /// data is left untouched.
+    pub fn set_weight(&mut self, id: EdgeId, weight: E::Weight) -> Result<E::Weight, GraphError>
+    where
+        E: EdgeWeight,
+    {
+        self.edge_mut(id).map(|e| {
+            let old = e.data.weight();
+            e.data.set_weight(weight);
+            old
+        })
+    }
+
+    /// Returns the ids of all the edges going from `from_id` to `to_id`, in the order they were
+    /// added. In an undirected graph that includes the edges added from `to_id` to `from_id`.
+    pub fn find_edges(
+        &self,
+        from_id: VertexId,
+        to_id: VertexId,
+    ) -> Result<impl Iterator<Item = EdgeId> + '_, GraphError> {
+        self.vertex(to_id)?;
+        Ok(self
+            .vertex(from_id)?
+            .out_edges
+            .iter()
+            .filter(move |e| e.other_id == to_id)
+            .map(|e| e.id))
+    }
+
+    /// The number of vertices in the graph, not counting the removed ones.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// The number of edges in the graph, not counting the removed ones. An undirected edge is
+    /// counted once.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Returns the live vertices as `(id, &data)` pairs, in the order of their ids.
+    pub fn vertices(&self) -> Vertices<'_, T> {
+        Vertices {
+            iter: self.vertices.iter().flatten(),
+        }
+    }
+
+    /// Returns the ids of the live vertices, in increasing order.
+    pub fn vertex_ids(&self) -> VertexIds<'_, T> {
+        VertexIds {
+            iter: self.vertices(),
+        }
+    }
+
+    /// Returns the live edges as `(from_id, to_id, &weight)` triples, in the order of their ids.
+    /// An undirected edge shows up once, with its endpoints in the order they were added in.
+    pub fn edges(&self) -> Edges<'_, E> {
+        Edges {
+            iter: self.edges.iter().flatten(),
+        }
+    }
+
+    /// Returns the ids of the vertices that `id` has an edge to. A vertex is reported once for
+    /// every edge `id` has to it.
+    pub fn neighbors(&self, id: VertexId) -> Result<Neighbors<'_>, GraphError> {
+        Ok(Neighbors {
+            iter: self.vertex(id)?.out_edges.iter(),
+        })
+    }
+
+    /// Returns the edges going out of `id` as `(to_id, &weight)` pairs.
+    pub fn out_edges(&self, id: VertexId) -> Result<AdjacentEdges<'_, E>, GraphError> {
+        Ok(AdjacentEdges {
+            iter: self.vertex(id)?.out_edges.iter(),
+            edges: &self.edges,
+        })
+    }
+
+    /// Returns the number of edges going out of `id`.
+    pub fn out_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        Ok(self.vertex(id)?.out_edges.len())
+    }
+
+    /// Returns the ids of the vertices that have an edge into `id`. A vertex is reported once
+    /// for every edge it has into `id`.
+    pub fn predecessors(&self, id: VertexId) -> Result<Neighbors<'_>, GraphError> {
+        Ok(Neighbors {
+            iter: self.vertex(id)?.in_edges.iter(),
+        })
+    }
+
+    /// Returns the number of edges coming into `id`.
+    pub fn in_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        Ok(self.vertex(id)?.in_edges.len())
+    }
+
+    /// Returns the edges coming into `id` as `(from_id, &weight)` pairs.
+    pub fn incoming_edges(&self, id: VertexId) -> Result<AdjacentEdges<'_, E>, GraphError> {
+        Ok(AdjacentEdges {
+            iter: self.vertex(id)?.in_edges.iter(),
+            edges: &self.edges,
+        })
+    }
+
+    fn vertex(&self, id: VertexId) -> Result<&Vertex<T>, GraphError> {
+        match self.vertices.get(id.index) {
+            Some(Some(vx)) if vx.id == id => Ok(vx),
+            Some(Some(_)) => Err(GraphError::StaleVertex(id)),
+            Some(None) => Err(GraphError::RemovedVertex(id)),
+            None => Err(GraphError::UnknownVertex(id)),
+        }
+    }
+
+    fn vertex_mut(&mut self, id: VertexId) -> Result<&mut Vertex<T>, GraphError> {
+        match self.vertices.get_mut(id.index) {
+            Some(Some(vx)) if vx.id == id => Ok(vx),
+            Some(Some(_)) => Err(GraphError::StaleVertex(id)),
+            Some(None) => Err(GraphError::RemovedVertex(id)),
+            None => Err(GraphError::UnknownVertex(id)),
+        }
+    }
+
+    fn edge(&self, id: EdgeId) -> Result<&EdgeRecord<E>, GraphError> {
+        match self.edges.get(id) {
+            Some(Some(edge)) => Ok(edge),
+            Some(None) => Err(GraphError::RemovedEdge(id)),
+            None => Err(GraphError::UnknownEdge(id)),
+        }
+    }
+
+    fn edge_mut(&mut self, id: EdgeId) -> Result<&mut EdgeRecord<E>, GraphError> {
+        match self.edges.get_mut(id) {
+            Some(Some(edge)) => Ok(edge),
+            Some(None) => Err(GraphError::RemovedEdge(id)),
+            None => Err(GraphError::UnknownEdge(id)),
+        }
+    }
+
+    /// Stores an edge in the free slot `id` of the edge pool and records it in the adjacency
+    /// lists of its endpoints, which must be live.
+    fn link(&mut self, id: EdgeId, from_id: VertexId, to_id: VertexId, data: E) {
+        debug_assert!(self.edges[id].is_none());
+        self.edges[id] = Some(EdgeRecord {
+            data,
+            from: from_id,
+            to: to_id,
         });
-        graph.extend_with_edges(&edges);
-        write_to_file(
-            format!("{}.dot", filename),
-            format!("{}", Dot::with_config(&graph, &[Config::EdgeNoLabel])),
-        );
+        self.edge_count += 1;
+        let live = "edges are only linked between live vertices";
+        self.vertex_mut(from_id).expect(live).add_out(id, to_id);
+        self.vertex_mut(to_id).expect(live).add_in(id, from_id);
+        if !Ty::is_directed() && from_id != to_id {
+            self.vertex_mut(to_id).expect(live).add_out(id, from_id);
+            self.vertex_mut(from_id).expect(live).add_in(id, to_id);
+        }
+    }
 
-        println!(
-            "Run: dot -Tpng {0}.dot -o {0}.png \nRun: open -a Preview {0}.png",
-            filename
-        );
+    /// Drops the edge `id` from the adjacency lists of its endpoints, whichever of them are still
+    /// around.
+    fn unlink(&mut self, id: EdgeId, from: VertexId, to: VertexId) {
+        let directed = Ty::is_directed();
+        if let Some(Some(vx)) = self.vertices.get_mut(from.index) {
+            vx.out_edges.retain(|e| e.id != id);
+            if !directed {
+                vx.in_edges.retain(|e| e.id != id);
+            }
+        }
+        if let Some(Some(vx)) = self.vertices.get_mut(to.index) {
+            vx.in_edges.retain(|e| e.id != id);
+            if !directed {
+                vx.out_edges.retain(|e| e.id != id);
+            }
+        }
+    }
+
+    /// Writes the graph in the graphviz dot format to `<filename>.dot`, labelling the vertices
+    /// with their index. A directed graph is written as a `digraph` and an undirected one as a
+    /// `graph`, with every edge showing up once.
+    pub fn draw(&self, filename: &str) {
+        write_dot(filename, self.dot());
+    }
+
+    /// Like [`Graph::draw`], but labels the vertices with their data and the edges with theirs.
+    pub fn draw_labelled(&self, filename: &str)
+    where
+        T: fmt::Display,
+        E: fmt::Display,
+    {
+        write_dot(filename, self.labelled_dot());
+    }
+
+    fn dot(&self) -> String {
+        let ids: Vec<_> = self.vertex_ids().collect();
+        // The petgraph nodes are dense, so they are labelled with our indices instead of theirs.
+        let graph = self
+            .as_petgraph()
+            .map(|n, _| ids[n.index()].index, |_, _| "");
+        format!("{}", Dot::with_config(&graph, &[Config::EdgeNoLabel]))
+    }
+
+    fn labelled_dot(&self) -> String
+    where
+        T: fmt::Display,
+        E: fmt::Display,
+    {
+        format!("{}", Dot::new(&self.as_petgraph()))
+    }
+}
+
+fn write_dot(filename: &str, dot: String) {
+    write_to_file(format!("{}.dot", filename), dot);
+
+    println!(
+        "Run: dot -Tpng {0}.dot -o {0}.png \nRun: open -a Preview {0}.png",
+        filename
+    );
+}
+
+/// Cloning a graph only needs its data to be `Clone`, whatever its edge type.
+impl<T: Clone, E: Clone, Ty> Clone for Graph<T, E, Ty> {
+    fn clone(&self) -> Self {
+        Self {
+            vertices: self.vertices.clone(),
+            edges: self.edges.clone(),
+            policy: self.policy.clone(),
+            node_count: self.node_count,
+            edge_count: self.edge_count,
+            generation: self.generation,
+            ty: PhantomData,
+        }
     }
 }
 
+/// Indexing a graph with a vertex id gives the data of the vertex. Panics if the id does not
+/// refer to a live vertex; use [`Graph::get`] to check instead.
+impl<T, E, Ty: EdgeType> Index<VertexId> for Graph<T, E, Ty> {
+    type Output = T;
+
+    fn index(&self, id: VertexId) -> &T {
+        match self.get(id) {
+            Ok(data) => data,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T, E, Ty: EdgeType> IndexMut<VertexId> for Graph<T, E, Ty> {
+    fn index_mut(&mut self, id: VertexId) -> &mut T {
+        match self.get_mut_data(id) {
+            Ok(data) => data,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// Adds the `(from_index, to_index, weight)` triples as edges, addressing the vertices by their
+/// [`VertexId::index`]. Vertices are created with the default data for every index up to the
+/// largest one mentioned, the same way a fresh graph would have handed them out.
+///
+/// Panics if an index belongs to a removed vertex or if the [`Policy`] of the graph rejects an
+/// edge.
+impl<T: Default, E, Ty: EdgeType> Extend<(usize, usize, E)> for Graph<T, E, Ty> {
+    fn extend<I: IntoIterator<Item = (usize, usize, E)>>(&mut self, iter: I) {
+        for (from, to, data) in iter {
+            while self.vertices.len() <= from.max(to) {
+                self.create_node(T::default());
+            }
+            let id = |index: usize| match self.vertex_id(index) {
+                Some(id) => id,
+                None => panic!("vertex at index {} has been removed", index),
+            };
+            let (from_id, to_id) = (id(from), id(to));
+            if let Err(err) = self.add_weighted_edge(from_id, to_id, data) {
+                panic!("{}", err);
+            }
+        }
+    }
+}
+
+impl<T: Default, E, Ty: EdgeType> FromIterator<(usize, usize, E)> for Graph<T, E, Ty> {
+    fn from_iter<I: IntoIterator<Item = (usize, usize, E)>>(iter: I) -> Self {
+        let mut graph = Self::default();
+        graph.extend(iter);
+        graph
+    }
+}
+
+impl<T: fmt::Debug, E: fmt::Debug, Ty: EdgeType> fmt::Debug for Graph<T, E, Ty> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("directed", &Ty::is_directed())
+            .field("vertices", &self.vertices().collect::<Vec<_>>())
+            .field("edges", &self.edges().collect::<Vec<_>>())
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// Two graphs are equal when the same ids, generations included, refer to live vertices and edges
+/// in both of them, with equal data and endpoints. The policies are not compared.
+impl<T: PartialEq, E: PartialEq, Ty: EdgeType> PartialEq for Graph<T, E, Ty> {
+    fn eq(&self, other: &Self) -> bool {
+        self.vertices.len() == other.vertices.len()
+            && self.edges.len() == other.edges.len()
+            && self
+                .vertices
+                .iter()
+                .zip(&other.vertices)
+                .all(|pair| match pair {
+                    (Some(a), Some(b)) => (a.id, &a.data) == (b.id, &b.data),
+                    (a, b) => a.is_none() && b.is_none(),
+                })
+            && self.edges.iter().zip(&other.edges).all(|pair| match pair {
+                (Some(a), Some(b)) => (a.from, a.to, &a.data) == (b.from, b.to, &b.data),
+                (a, b) => a.is_none() && b.is_none(),
+            })
+    }
+}
+
+/// Drops the `None` slots of `pool` and returns the old index -> new index table.
+fn compact<X>(pool: &mut Vec<Option<X>>) -> Vec<Option<usize>> {
+    let mut next_idx = 0;
+    let remap = pool
+        .iter()
+        .map(|slot| {
+            slot.as_ref().map(|_| {
+                next_idx += 1;
+                next_idx - 1
+            })
+        })
+        .collect();
+    pool.retain(Option::is_some);
+    remap
+}
+
 fn write_to_file(filename: String, data: String) {
     let path = Path::new(&filename);
     let display = path.display();
 
     // Open a file in write-only mode, returns `io::Result<File>`
-    let mut file = match File::create(&path) {
+    let mut file = match File::create(path) {
         Err(why) => panic!("couldn't create {}: {}", display, why),
         Ok(file) => file,
     };
@@ -139,7 +832,16 @@ fn write_to_file(filename: String, data: String) {
 
 #[cfg(test)]
 mod tests {
-    use crate::Graph;
+    use std::time::Duration;
+
+    use crate::{EdgeType, EdgeWeight, Graph, GraphError, Policy, Total, Undirected, VertexId};
+
+    fn vid(index: usize) -> VertexId {
+        VertexId {
+            index,
+            generation: 0,
+        }
+    }
 
     #[test]
     fn it_works() {
@@ -148,8 +850,8 @@ mod tests {
         let b = g.create_node(1);
         let c = g.create_node(2);
 
-        g.add_weighted_edge(a, c, 1);
-        g.add_weighted_edge(b, c, 2);
+        g.add_weighted_edge(a, c, 1).unwrap();
+        g.add_weighted_edge(b, c, 2).unwrap();
 
         assert_eq!(g.get_mut_data(a).unwrap(), &mut 0);
         assert_eq!(g.get_mut_data(b).unwrap(), &mut 1);
@@ -157,4 +859,335 @@ mod tests {
 
         g.draw("my_graph");
     }
+
+    #[test]
+    fn edges_to_unknown_vertices_are_rejected() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+
+        assert_eq!(
+            g.add_edge(a, vid(7)),
+            Err(GraphError::UnknownVertex(vid(7)))
+        );
+        assert_eq!(
+            g.add_weighted_edge(vid(7), a, 3),
+            Err(GraphError::UnknownVertex(vid(7)))
+        );
+        assert_eq!(
+            g.get_mut_data(vid(1)),
+            Err(GraphError::UnknownVertex(vid(1)))
+        );
+        assert_eq!(g.out_degree(a), Ok(0));
+    }
+
+    #[test]
+    fn in_edges_track_out_edges() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        g.add_weighted_edge(a, c, 1).unwrap();
+        g.add_weighted_edge(b, c, 2).unwrap();
+        g.add_edge(c, a).unwrap();
+
+        assert_eq!(g.in_degree(c), Ok(2));
+        assert_eq!(g.in_degree(b), Ok(0));
+        assert_eq!(g.predecessors(c).unwrap().collect::<Vec<_>>(), vec![a, b]);
+        assert_eq!(
+            g.incoming_edges(a).unwrap().collect::<Vec<_>>(),
+            vec![(c, &0)]
+        );
+        assert!(g.predecessors(vid(9)).is_err());
+    }
+
+    #[test]
+    fn remove_vertex_then_vacuum() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        let d = g.create_node("d");
+        g.add_weighted_edge(a, b, 1).unwrap();
+        g.add_weighted_edge(b, c, 2).unwrap();
+        g.add_weighted_edge(c, b, 3).unwrap();
+        g.add_weighted_edge(b, b, 4).unwrap();
+        g.add_weighted_edge(c, d, 5).unwrap();
+
+        assert_eq!(g.remove_vertex(b), Ok("b"));
+        assert_eq!(g.remove_vertex(b), Err(GraphError::RemovedVertex(b)));
+        assert_eq!(g.add_edge(a, b), Err(GraphError::RemovedVertex(b)));
+        assert_eq!(g.in_degree(c), Ok(0));
+        assert_eq!(g.get_mut_data(d), Ok(&mut "d"));
+
+        let remap = g.vacuum();
+        let (old_c, old_d) = (c, d);
+        let (c, d) = (remap.vertex(c).unwrap(), remap.vertex(d).unwrap());
+        assert_eq!(
+            remap
+                .vertices()
+                .map(|(_, new)| new.index())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(remap.vertex(a), Some(a));
+        assert_eq!(remap.vertex(b), None);
+        assert_eq!(g.get_mut_data(c), Ok(&mut "c"));
+        assert_eq!(g.predecessors(d).unwrap().collect::<Vec<_>>(), vec![c]);
+        assert_eq!(g.get(old_c), Err(GraphError::StaleVertex(old_c)));
+        assert_eq!(g.get(old_d), Err(GraphError::UnknownVertex(old_d)));
+
+        // A vertex that lands on the index of a stale handle does not answer to it.
+        let e = g.create_node("e");
+        assert_eq!(e.index(), old_d.index());
+        assert_eq!(g.get(old_d), Err(GraphError::StaleVertex(old_d)));
+        assert_eq!(g.vertex_id(e.index()), Some(e));
+    }
+
+    #[test]
+    fn edges_have_identities() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        let ab1 = g.add_weighted_edge(a, b, 1).unwrap();
+        let ab2 = g.add_weighted_edge(a, b, 2).unwrap();
+        let bc = g.add_weighted_edge(b, c, 3).unwrap();
+
+        assert_eq!(
+            g.find_edges(a, b).unwrap().collect::<Vec<_>>(),
+            vec![ab1, ab2]
+        );
+        assert_eq!(g.edge_endpoints(bc), Ok((b, c)));
+        assert_eq!(g.set_weight(ab2, 7), Ok(2));
+        assert_eq!(g.edge_weight(ab2), Ok(7));
+
+        assert_eq!(g.remove_edge(ab1), Ok(1));
+        assert_eq!(g.remove_edge(ab1), Err(GraphError::RemovedEdge(ab1)));
+        assert_eq!(g.edge_weight(42), Err(GraphError::UnknownEdge(42)));
+        assert_eq!(g.find_edges(a, b).unwrap().collect::<Vec<_>>(), vec![ab2]);
+        assert_eq!(
+            g.incoming_edges(b).unwrap().collect::<Vec<_>>(),
+            vec![(a, &7)]
+        );
+
+        g.remove_vertex(a).unwrap();
+        assert_eq!(g.edge_endpoints(ab2), Err(GraphError::RemovedEdge(ab2)));
+        let remap = g.vacuum();
+        assert_eq!(remap.edges(), &[None, None, Some(0)]);
+        let (b, c) = (remap.vertex(b).unwrap(), remap.vertex(c).unwrap());
+        assert_eq!(g.edge_endpoints(remap.edge(bc).unwrap()), Ok((b, c)));
+        assert_eq!(g.in_degree(b), Ok(0));
+    }
+
+    #[test]
+    fn weights_are_generic() {
+        let mut g: Graph<&str, Total<f64>> = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let ab = g.add_weighted_edge(a, b, Total(0.5)).unwrap();
+        let ba = g.add_edge(b, a).unwrap();
+        assert_eq!(g.edge_weight(ab), Ok(Total(0.5)));
+        assert_eq!(g.edge_weight(ba), Ok(Total(0.0)));
+
+        let mut g: Graph<(), Duration> = Graph::new();
+        let a = g.create_node(());
+        let ab = g.add_weighted_edge(a, a, Duration::from_millis(3)).unwrap();
+        assert_eq!(g.edge_weight(ab), Ok(Duration::from_millis(3)));
+    }
+
+    #[test]
+    fn edges_carry_payloads() {
+        #[derive(Debug, PartialEq)]
+        struct Call {
+            latency: u32,
+            protocol: &'static str,
+        }
+
+        impl EdgeWeight for Call {
+            type Weight = u32;
+
+            fn weight(&self) -> u32 {
+                self.latency
+            }
+
+            fn set_weight(&mut self, weight: u32) {
+                self.latency = weight;
+            }
+        }
+
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let grpc = g
+            .add_weighted_edge(
+                a,
+                b,
+                Call {
+                    latency: 3,
+                    protocol: "grpc",
+                },
+            )
+            .unwrap();
+        let http = g
+            .add_weighted_edge(
+                a,
+                b,
+                Call {
+                    latency: 9,
+                    protocol: "http",
+                },
+            )
+            .unwrap();
+
+        assert_eq!(g.edge_data(http).unwrap().protocol, "http");
+        assert_eq!(g.set_weight(grpc, 4), Ok(3));
+        g.edge_data_mut(grpc).unwrap().protocol = "grpc+tls";
+        assert_eq!(
+            g.edge_data(grpc),
+            Ok(&Call {
+                latency: 4,
+                protocol: "grpc+tls"
+            })
+        );
+
+        let mut g = Graph::new();
+        let a = g.create_node(());
+        let aa = g.add_weighted_edge(a, a, (2u8, "loop")).unwrap();
+        assert_eq!(g.set_weight(aa, 5), Ok(2));
+        assert_eq!(g.edge_data(aa), Ok(&(5, "loop")));
+    }
+
+    #[test]
+    fn iterators_skip_removed_vertices() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        let d = g.create_node("d");
+        g.add_weighted_edge(a, b, 1).unwrap();
+        g.add_weighted_edge(a, c, 2).unwrap();
+        g.add_weighted_edge(b, c, 3).unwrap();
+        g.add_weighted_edge(c, d, 4).unwrap();
+        assert_eq!((g.node_count(), g.edge_count()), (4, 4));
+
+        g.remove_vertex(b).unwrap();
+        assert_eq!((g.node_count(), g.edge_count()), (3, 2));
+        assert_eq!(g.vertex_ids().collect::<Vec<_>>(), vec![a, c, d]);
+        assert_eq!(
+            g.vertices().collect::<Vec<_>>(),
+            vec![(a, &"a"), (c, &"c"), (d, &"d")]
+        );
+        assert_eq!(g.edges().collect::<Vec<_>>(), vec![(a, c, &2), (c, d, &4)]);
+        assert_eq!(g.neighbors(a).unwrap().collect::<Vec<_>>(), vec![c]);
+        assert_eq!(g.out_edges(c).unwrap().collect::<Vec<_>>(), vec![(d, &4)]);
+        assert_eq!(g.out_degree(a), Ok(1));
+        assert!(g.neighbors(b).is_err());
+
+        let mut g = Graph::new_undirected();
+        let a = g.create_node(());
+        let b = g.create_node(());
+        g.add_weighted_edge(a, b, 1).unwrap();
+        assert_eq!(g.edge_count(), 1);
+        assert_eq!(g.edges().count(), 1);
+        assert_eq!(g.neighbors(b).unwrap().collect::<Vec<_>>(), vec![a]);
+    }
+
+    #[test]
+    fn std_traits() {
+        let mut g: Graph<String, u32> = vec![(0, 1, 5), (1, 3, 7)].into_iter().collect();
+        assert_eq!(g.node_count(), 4);
+        assert_eq!(g.get(vid(2)), Ok(&String::new()));
+        g[vid(2)].push_str("two");
+        assert_eq!(g[vid(2)], "two");
+        assert_eq!(g.get(vid(4)), Err(GraphError::UnknownVertex(vid(4))));
+
+        fn copy<Ty: EdgeType>(g: &Graph<String, u32, Ty>) -> Graph<String, u32, Ty> {
+            g.clone()
+        }
+        let copy = copy(&g);
+        assert_eq!(copy, g);
+        g.extend(vec![(4, 0, 1)]);
+        assert_ne!(copy, g);
+        assert_eq!(g.node_count(), 5);
+        assert_eq!(g.edges().last(), Some((vid(4), vid(0), &1)));
+        assert!(format!("{:?}", copy).contains(
+            "(VertexId { index: 1, generation: 0 }, VertexId { index: 3, generation: 0 }, 7)"
+        ));
+    }
+
+    #[test]
+    fn equality_compares_generations() {
+        let mut g: Graph<&str, i32> = Graph::new();
+        let a = g.create_node("a");
+        g.create_node("b");
+        g.remove_vertex(a).unwrap();
+        g.vacuum();
+
+        let mut h: Graph<&str, i32> = Graph::new();
+        let b = h.create_node("b");
+        assert_eq!(g.get(b), Err(GraphError::StaleVertex(b)));
+        assert_ne!(g, h);
+        assert_eq!(g, g.clone());
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex 1@0 has been removed")]
+    fn indexing_a_removed_vertex_panics() {
+        let mut g: Graph<u8, u8> = vec![(0, 1, 0)].into_iter().collect();
+        g.remove_vertex(vid(1)).unwrap();
+        let _ = g[vid(1)];
+    }
+
+    #[test]
+    fn policy_is_enforced() {
+        let mut g: Graph<&str, u32> = Graph::with_policy(Policy::simple());
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let ab = g.add_weighted_edge(a, b, 1).unwrap();
+        assert_eq!(
+            g.add_weighted_edge(a, b, 2),
+            Err(GraphError::DuplicateEdge(ab))
+        );
+        assert_eq!(g.add_edge(a, a), Err(GraphError::SelfLoop(a)));
+        assert!(g.add_edge(b, a).is_ok());
+
+        let mut g = Graph::<&str, u32, Undirected>::with_policy(
+            Policy::multigraph().merge_parallel_edges(|wt: &mut u32, new| *wt = (*wt).min(new)),
+        );
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let ab = g.add_weighted_edge(a, b, 5).unwrap();
+        assert_eq!(g.add_weighted_edge(b, a, 3), Ok(ab));
+        assert_eq!(g.add_weighted_edge(a, b, 4), Ok(ab));
+        assert_eq!(g.edge_weight(ab), Ok(3));
+        assert_eq!(g.in_degree(a), Ok(1));
+        assert_eq!(g.add_edge(a, a).map(|_| ()), Ok(()));
+    }
+
+    #[test]
+    fn undirected_edges_are_symmetric() {
+        let mut g = Graph::new_undirected();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        let ab = g.add_weighted_edge(a, b, 1).unwrap();
+        g.add_weighted_edge(c, b, 2).unwrap();
+        g.add_weighted_edge(c, c, 3).unwrap();
+
+        assert!(!g.is_directed());
+        assert_eq!(g.find_edges(b, a).unwrap().collect::<Vec<_>>(), vec![ab]);
+        assert_eq!(g.predecessors(b).unwrap().collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(g.predecessors(a).unwrap().collect::<Vec<_>>(), vec![b]);
+        assert_eq!(g.in_degree(c), Ok(2));
+        assert!(g.dot().starts_with("graph {"));
+        assert!(g.dot().contains("2 -- 1 [ ]"));
+        assert!(g.labelled_dot().contains(r#"0 [ label = "a" ]"#));
+        assert!(g.labelled_dot().contains(r#"2 -- 1 [ label = "2" ]"#));
+
+        g.remove_edge(ab).unwrap();
+        assert_eq!(g.in_degree(a), Ok(0));
+        g.remove_vertex(c).unwrap();
+        assert_eq!(g.in_degree(b), Ok(0));
+        assert_eq!(g.edges.iter().flatten().count(), 0);
+    }
 }
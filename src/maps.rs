@@ -0,0 +1,281 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{EdgeId, EdgeType, Graph, GraphError, Remapping, VertexId};
+
+/// The dense storage shared by the side tables: a value per index, with every index past the end
+/// of the vector holding the default value.
+#[derive(Debug, Clone)]
+struct Slots<V> {
+    values: Vec<V>,
+    default: V,
+}
+
+impl<V: Clone> Slots<V> {
+    fn new(len: usize, default: V) -> Self {
+        Self {
+            values: vec![default.clone(); len],
+            default,
+        }
+    }
+
+    fn get(&self, index: usize) -> &V {
+        self.values.get(index).unwrap_or(&self.default)
+    }
+
+    fn get_mut(&mut self, index: usize) -> &mut V {
+        if index >= self.values.len() {
+            self.values.resize(index + 1, self.default.clone());
+        }
+        &mut self.values[index]
+    }
+
+    /// Moves the value at every old index to `new_index(old)`, dropping the ones mapped to `None`.
+    fn remap(&mut self, len: usize, new_index: impl Fn(usize) -> Option<usize>) {
+        let mut values = vec![self.default.clone(); len];
+        for (old, value) in self.values.drain(..).enumerate() {
+            if let Some(new) = new_index(old) {
+                values[new] = value;
+            }
+        }
+        self.values = values;
+    }
+}
+
+/// A value per vertex of a graph, stored densely by [`VertexId::index`]. Every vertex starts out
+/// with the default value, including the ones added to the graph after the map was made.
+///
+/// A vacuum moves the vertices around, so the map has to be [`remap`](VertexMap::remap)ped with
+/// the [`Remapping`] the vacuum returned. Until then the map is stale, and looking it up with a
+/// vertex handed out after the vacuum panics. Likewise, once the map is remapped, looking it up
+/// with a handle that went stale in the vacuum panics.
+#[derive(Debug, Clone)]
+pub struct VertexMap<V> {
+    slots: Slots<V>,
+    /// The handle of the vertex in every slot, as of the creation or the last remap of the map.
+    ids: Vec<Option<VertexId>>,
+    generation: u64,
+}
+
+impl<V: Clone> VertexMap<V> {
+    /// Creates a map for the vertices of `graph`, holding `default` for all of them.
+    pub fn new<T, E, Ty: EdgeType>(graph: &Graph<T, E, Ty>, default: V) -> Self {
+        Self {
+            slots: Slots::new(graph.node_bound(), default),
+            ids: (0..graph.node_bound())
+                .map(|index| graph.vertex_id(index))
+                .collect(),
+            generation: graph.generation(),
+        }
+    }
+
+    /// Returns the value of the vertex `id`.
+    pub fn get(&self, id: VertexId) -> &V {
+        self.check(id);
+        self.slots.get(id.index())
+    }
+
+    /// Returns a mutable reference to the value of the vertex `id`.
+    pub fn get_mut(&mut self, id: VertexId) -> &mut V {
+        self.check(id);
+        self.slots.get_mut(id.index())
+    }
+
+    /// Sets the value of the vertex `id` and returns the previous one.
+    pub fn insert(&mut self, id: VertexId, value: V) -> V {
+        std::mem::replace(self.get_mut(id), value)
+    }
+
+    /// Whether the map is up to date with the vacuums of `graph`.
+    pub fn is_valid_for<T, E, Ty: EdgeType>(&self, graph: &Graph<T, E, Ty>) -> bool {
+        self.generation == graph.generation()
+    }
+
+    /// Moves the values along with the vertices after a vacuum. The values of the removed
+    /// vertices are dropped. Fails if the map missed an earlier vacuum.
+    pub fn remap(&mut self, remap: &Remapping) -> Result<(), GraphError> {
+        if remap.generation != self.generation + 1 {
+            return Err(GraphError::StaleMap);
+        }
+        let len = remap.vertices().count();
+        self.slots.remap(len, |old| {
+            remap
+                .vertices
+                .get(old)
+                .copied()
+                .flatten()
+                .map(|(_, new)| new.index())
+        });
+        self.ids = vec![None; len];
+        for (_, new) in remap.vertices() {
+            self.ids[new.index()] = Some(new);
+        }
+        self.generation = remap.generation;
+        Ok(())
+    }
+
+    fn check(&self, id: VertexId) {
+        assert!(
+            id.generation() <= self.generation,
+            "vertex {} is newer than the map, which has to be remapped after a vacuum",
+            id
+        );
+        // The vertices added since the creation or the last remap of the map all have its
+        // generation.
+        let current = match self.ids.get(id.index()) {
+            Some(&Some(known)) => known == id,
+            Some(None) => true,
+            None => id.generation() == self.generation,
+        };
+        assert!(current, "vertex handle {} is stale", id);
+    }
+}
+
+impl<V: Clone> Index<VertexId> for VertexMap<V> {
+    type Output = V;
+
+    fn index(&self, id: VertexId) -> &V {
+        self.get(id)
+    }
+}
+
+impl<V: Clone> IndexMut<VertexId> for VertexMap<V> {
+    fn index_mut(&mut self, id: VertexId) -> &mut V {
+        self.get_mut(id)
+    }
+}
+
+/// A value per edge of a graph, stored densely by [`EdgeId`]. Every edge starts out with the
+/// default value, including the ones added to the graph after the map was made.
+///
+/// Like a [`VertexMap`], the map has to be [`remap`](EdgeMap::remap)ped after a vacuum. Edge ids
+/// carry no generation, so it is up to the caller to check [`EdgeMap::is_valid_for`].
+#[derive(Debug, Clone)]
+pub struct EdgeMap<V> {
+    slots: Slots<V>,
+    generation: u64,
+}
+
+impl<V: Clone> EdgeMap<V> {
+    /// Creates a map for the edges of `graph`, holding `default` for all of them.
+    pub fn new<T, E, Ty: EdgeType>(graph: &Graph<T, E, Ty>, default: V) -> Self {
+        Self {
+            slots: Slots::new(graph.edge_bound(), default),
+            generation: graph.generation(),
+        }
+    }
+
+    /// Returns the value of the edge `id`.
+    pub fn get(&self, id: EdgeId) -> &V {
+        self.slots.get(id)
+    }
+
+    /// Returns a mutable reference to the value of the edge `id`.
+    pub fn get_mut(&mut self, id: EdgeId) -> &mut V {
+        self.slots.get_mut(id)
+    }
+
+    /// Sets the value of the edge `id` and returns the previous one.
+    pub fn insert(&mut self, id: EdgeId, value: V) -> V {
+        std::mem::replace(self.get_mut(id), value)
+    }
+
+    /// Whether the map is up to date with the vacuums of `graph`.
+    pub fn is_valid_for<T, E, Ty: EdgeType>(&self, graph: &Graph<T, E, Ty>) -> bool {
+        self.generation == graph.generation()
+    }
+
+    /// Moves the values along with the edges after a vacuum. The values of the removed edges are
+    /// dropped. Fails if the map missed an earlier vacuum.
+    pub fn remap(&mut self, remap: &Remapping) -> Result<(), GraphError> {
+        if remap.generation != self.generation + 1 {
+            return Err(GraphError::StaleMap);
+        }
+        let len = remap.edges().iter().flatten().count();
+        self.slots.remap(len, |old| remap.edge(old));
+        self.generation = remap.generation;
+        Ok(())
+    }
+}
+
+impl<V: Clone> Index<EdgeId> for EdgeMap<V> {
+    type Output = V;
+
+    fn index(&self, id: EdgeId) -> &V {
+        self.get(id)
+    }
+}
+
+impl<V: Clone> IndexMut<EdgeId> for EdgeMap<V> {
+    fn index_mut(&mut self, id: EdgeId) -> &mut V {
+        self.get_mut(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Graph, GraphError};
+
+    #[test]
+    fn maps_follow_the_graph() {
+        let mut g = Graph::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let ab = g.add_weighted_edge(a, b, 1).unwrap();
+        let mut dist = g.vertex_map(u32::MAX);
+        let mut seen = g.edge_map(false);
+        dist[a] = 0;
+        seen[ab] = true;
+
+        let c = g.create_node("c");
+        let bc = g.add_weighted_edge(b, c, 2).unwrap();
+        assert_eq!(dist[c], u32::MAX);
+        dist[c] = 3;
+        assert!(!seen[bc]);
+
+        g.remove_vertex(a).unwrap();
+        let remap = g.vacuum();
+        assert!(!dist.is_valid_for(&g));
+        dist.remap(&remap).unwrap();
+        seen.remap(&remap).unwrap();
+        assert!(dist.is_valid_for(&g) && seen.is_valid_for(&g));
+        assert_eq!(dist[remap.vertex(c).unwrap()], 3);
+        assert_eq!(dist[remap.vertex(b).unwrap()], u32::MAX);
+        assert!(!seen[remap.edge(bc).unwrap()]);
+
+        let mut old = g.vertex_map(0);
+        g.vacuum();
+        let remap = g.vacuum();
+        assert_eq!(old.remap(&remap), Err(GraphError::StaleMap));
+    }
+
+    #[test]
+    #[should_panic(expected = "has to be remapped")]
+    fn stale_vertex_map_panics() {
+        let mut g: Graph<(), i32> = Graph::new();
+        let a = g.create_node(());
+        let map = g.vertex_map(0);
+        g.remove_vertex(a).unwrap();
+        g.vacuum();
+        let b = g.create_node(());
+        let _ = map[b];
+    }
+
+    #[test]
+    #[should_panic(expected = "is stale")]
+    fn remapped_vertex_map_rejects_stale_handles() {
+        let mut g = Graph::<_, i32>::new();
+        let a = g.create_node("a");
+        let b = g.create_node("b");
+        let c = g.create_node("c");
+        let mut map = g.vertex_map(0);
+        map[b] = 1;
+        map[c] = 2;
+
+        g.remove_vertex(a).unwrap();
+        let remap = g.vacuum();
+        map.remap(&remap).unwrap();
+        assert_eq!(map[remap.vertex(b).unwrap()], 1);
+        assert_eq!(map[remap.vertex(c).unwrap()], 2);
+        let _ = map[b];
+    }
+}
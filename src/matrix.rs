@@ -0,0 +1,347 @@
+use std::{marker::PhantomData, ops::Index};
+
+use crate::{
+    visit::{EdgeRef, EdgeWeights, GraphBase, InEdges, NodeCount, NodeIndexable, Nodes, OutEdges},
+    Directed, EdgeType, EdgeWeight, Graph, Undirected, Weight,
+};
+
+/// A dense graph storing the weight of the edge between every pair of vertices in an `n * n`
+/// matrix, for small dense graphs where looking up an edge in constant time matters more than
+/// memory. The vertices are addressed by their index, `0..n`, and methods given an index out of
+/// that range panic like slices do. There is at most one edge from a vertex to another; the
+/// matrix of an undirected graph is kept symmetric.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyMatrix<T, W, Ty = Directed> {
+    vertices: Vec<T>,
+
+    /// The weights, row by row: `cells[from * n + to]`.
+    cells: Vec<Option<W>>,
+
+    ty: PhantomData<Ty>,
+}
+
+impl<T, W: Weight> AdjacencyMatrix<T, W> {
+    /// Creates an empty directed matrix.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, W: Weight> AdjacencyMatrix<T, W, Undirected> {
+    /// Creates an empty undirected matrix.
+    pub fn new_undirected() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> Default for AdjacencyMatrix<T, W, Ty> {
+    fn default() -> Self {
+        Self {
+            vertices: vec![],
+            cells: vec![],
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> AdjacencyMatrix<T, W, Ty> {
+    /// Adds a vertex without edges and returns its index. This copies the whole matrix.
+    pub fn add_vertex(&mut self, data: T) -> usize {
+        let n = self.vertices.len();
+        let mut cells = Vec::with_capacity((n + 1) * (n + 1));
+        for row in self.cells.chunks(n.max(1)).take(n) {
+            cells.extend_from_slice(row);
+            cells.push(None);
+        }
+        cells.extend((0..=n).map(|_| None));
+        self.cells = cells;
+        self.vertices.push(data);
+        n
+    }
+
+    /// The number of vertices.
+    pub fn node_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// The number of edges. An undirected edge is counted once.
+    pub fn edge_count(&self) -> usize {
+        let n = self.vertices.len();
+        if Ty::is_directed() {
+            self.cells.iter().flatten().count()
+        } else {
+            (0..n)
+                .map(|i| (i..n).filter(|&j| self.has_edge(i, j)).count())
+                .sum()
+        }
+    }
+
+    /// Returns the data stored in the vertex `index`, if there is one.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.vertices.get(index)
+    }
+
+    /// Whether there is an edge from `from` to `to`.
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.weight(from, to).is_some()
+    }
+
+    /// The weight of the edge from `from` to `to`, if there is one.
+    pub fn weight(&self, from: usize, to: usize) -> Option<W> {
+        self.cells[self.cell(from, to)]
+    }
+
+    /// Adds an edge from `from` to `to`, or changes its weight if there already is one, and
+    /// returns the previous weight.
+    pub fn set_edge(&mut self, from: usize, to: usize, weight: W) -> Option<W> {
+        let cell = self.cell(from, to);
+        if !Ty::is_directed() {
+            let mirror = self.cell(to, from);
+            self.cells[mirror] = Some(weight);
+        }
+        self.cells[cell].replace(weight)
+    }
+
+    /// Removes the edge from `from` to `to` and returns its weight, if there was one.
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> Option<W> {
+        let cell = self.cell(from, to);
+        if !Ty::is_directed() {
+            let mirror = self.cell(to, from);
+            self.cells[mirror] = None;
+        }
+        self.cells[cell].take()
+    }
+
+    /// The vertices that `index` has an edge to, in increasing order.
+    pub fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.row(index)
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.is_some())
+            .map(|(to, _)| to)
+    }
+
+    /// The vertices that have an edge into `index`, in increasing order.
+    pub fn predecessors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.vertices.len()).filter(move |&from| self.has_edge(from, index))
+    }
+
+    /// The weights of the edges going out of `index`, with `None` where there is no edge.
+    pub fn row(&self, index: usize) -> &[Option<W>] {
+        let n = self.vertices.len();
+        assert!(
+            index < n,
+            "vertex {} out of range for {} vertices",
+            index,
+            n
+        );
+        &self.cells[index * n..(index + 1) * n]
+    }
+
+    /// The whole matrix as a vector of rows.
+    pub fn to_rows(&self) -> Vec<Vec<Option<W>>> {
+        (0..self.vertices.len())
+            .map(|i| self.row(i).to_vec())
+            .collect()
+    }
+
+    /// The whole matrix as a flat buffer of `n * n` cells in row-major order: the weight of the
+    /// edge from `i` to `j` is at `i * n + j`.
+    pub fn as_row_major(&self) -> &[Option<W>] {
+        &self.cells
+    }
+
+    fn cell(&self, from: usize, to: usize) -> usize {
+        let n = self.vertices.len();
+        assert!(
+            from < n && to < n,
+            "edge {} -> {} out of range for {} vertices",
+            from,
+            to,
+            n
+        );
+        from * n + to
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> Index<usize> for AdjacencyMatrix<T, W, Ty> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.vertices[index]
+    }
+}
+
+/// Builds the matrix of a graph. The live vertices get the indices `0..n` in the order of
+/// [`Graph::vertex_ids`], and the matrix keeps the lightest of parallel edges.
+impl<T, E, Ty> From<&Graph<T, E, Ty>> for AdjacencyMatrix<T, E::Weight, Ty>
+where
+    T: Clone,
+    E: EdgeWeight,
+    Ty: EdgeType,
+{
+    fn from(graph: &Graph<T, E, Ty>) -> Self {
+        let mut index = vec![0; graph.node_bound()];
+        let mut matrix = Self::default();
+        for (id, data) in graph.vertices() {
+            index[id.index()] = matrix.vertices.len();
+            matrix.vertices.push(data.clone());
+        }
+        let n = matrix.vertices.len();
+        matrix.cells = vec![None; n * n];
+        for (from, to, data) in graph.edges() {
+            let (from, to) = (index[from.index()], index[to.index()]);
+            let weight = match matrix.weight(from, to) {
+                Some(w) if w <= data.weight() => w,
+                _ => data.weight(),
+            };
+            matrix.set_edge(from, to, weight);
+        }
+        matrix
+    }
+}
+
+/// Builds a graph with the vertices of the matrix, the vertex `i` getting the index `i`.
+impl<T, W: Weight, Ty: EdgeType> From<AdjacencyMatrix<T, W, Ty>> for Graph<T, W, Ty> {
+    fn from(matrix: AdjacencyMatrix<T, W, Ty>) -> Self {
+        let n = matrix.vertices.len();
+        let mut graph = Graph::default();
+        let ids: Vec<_> = matrix
+            .vertices
+            .into_iter()
+            .map(|data| graph.create_node(data))
+            .collect();
+        for (cell, weight) in matrix.cells.into_iter().enumerate() {
+            let (from, to) = (cell / n, cell % n);
+            if let Some(weight) = weight {
+                if Ty::is_directed() || from <= to {
+                    graph
+                        .add_weighted_edge(ids[from], ids[to], weight)
+                        .expect("the default policy accepts any edge");
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> GraphBase for AdjacencyMatrix<T, W, Ty> {
+    type NodeId = usize;
+    /// An edge is identified by its endpoints.
+    type EdgeId = (usize, usize);
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> NodeCount for AdjacencyMatrix<T, W, Ty> {
+    fn node_count(&self) -> usize {
+        self.vertices.len()
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> NodeIndexable for AdjacencyMatrix<T, W, Ty> {
+    fn node_bound(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn to_index(&self, id: usize) -> usize {
+        id
+    }
+
+    fn node_at(&self, index: usize) -> Option<usize> {
+        Some(index).filter(|&i| i < self.vertices.len())
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> Nodes for AdjacencyMatrix<T, W, Ty> {
+    fn node_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        0..self.vertices.len()
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> OutEdges for AdjacencyMatrix<T, W, Ty> {
+    fn out_edge_refs(
+        &self,
+        id: usize,
+    ) -> impl Iterator<Item = EdgeRef<usize, (usize, usize)>> + '_ {
+        self.neighbors(id).map(move |to| EdgeRef {
+            id: (id, to),
+            source: id,
+            target: to,
+        })
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> InEdges for AdjacencyMatrix<T, W, Ty> {
+    fn in_edge_refs(&self, id: usize) -> impl Iterator<Item = EdgeRef<usize, (usize, usize)>> + '_ {
+        self.row(id);
+        self.predecessors(id).map(move |from| EdgeRef {
+            id: (from, id),
+            source: from,
+            target: id,
+        })
+    }
+}
+
+impl<T, W: Weight, Ty: EdgeType> EdgeWeights for AdjacencyMatrix<T, W, Ty> {
+    type Weight = W;
+
+    fn weight_of(&self, (from, to): (usize, usize)) -> Option<W> {
+        let n = self.vertices.len();
+        if from < n && to < n {
+            self.weight(from, to)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph, AdjacencyMatrix, Graph, InEdges};
+
+    #[test]
+    fn matrix_round_trip() {
+        let (mut g, ids) = graph! { "a" -> "b" : 5, "b" -> "c" : 2, "a" -> "b" : 3 };
+        g.remove_vertex(ids["c"]).unwrap();
+        let d = g.create_node("d");
+        g.add_weighted_edge(d, ids["a"], 7).unwrap();
+
+        let m = AdjacencyMatrix::from(&g);
+        assert_eq!(m.node_count(), 3);
+        assert_eq!((m[0], m[2]), ("a", "d"));
+        assert!(m.has_edge(0, 1) && !m.has_edge(1, 0));
+        assert_eq!(m.weight(0, 1), Some(3));
+        assert_eq!(
+            m.to_rows(),
+            vec![
+                vec![None, Some(3), None],
+                vec![None, None, None],
+                vec![Some(7), None, None]
+            ]
+        );
+        assert_eq!(m.as_row_major()[2 * 3], Some(7));
+        assert_eq!(m.in_neighbors(0).collect::<Vec<_>>(), vec![2]);
+
+        let back = Graph::from(m);
+        assert_eq!(back.edge_count(), 2);
+        assert_eq!(
+            back.vertices().map(|(_, &n)| n).collect::<Vec<_>>(),
+            vec!["a", "b", "d"]
+        );
+    }
+
+    #[test]
+    fn undirected_matrix_is_symmetric() {
+        let mut m = AdjacencyMatrix::<(), u8, _>::new_undirected();
+        let (a, b) = (m.add_vertex(()), m.add_vertex(()));
+        m.add_vertex(());
+        assert_eq!(m.set_edge(a, b, 4), None);
+        assert_eq!(m.weight(b, a), Some(4));
+        assert_eq!(m.edge_count(), 1);
+        assert_eq!(m.remove_edge(b, a), Some(4));
+        assert!(!m.has_edge(a, b));
+    }
+}
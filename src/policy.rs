@@ -0,0 +1,98 @@
+use std::{fmt, sync::Arc};
+
+/// Folds the data of a new parallel edge (the second argument) into the data of the existing edge.
+pub type Combiner<E> = Arc<dyn Fn(&mut E, E) + Send + Sync>;
+
+/// What a [`Graph`](crate::Graph) does when an edge is added between two vertices that are
+/// already joined by an edge.
+pub enum ParallelEdges<E> {
+    /// The new edge is added next to the existing ones. This makes the graph a multigraph.
+    Allow,
+    /// The new edge is rejected with [`GraphError::DuplicateEdge`](crate::GraphError).
+    Deny,
+    /// The data of the new edge is folded into the existing edge by the combiner, which gets the
+    /// existing data and the new data. No new edge is created.
+    Merge(Combiner<E>),
+}
+
+impl<E> Clone for ParallelEdges<E> {
+    fn clone(&self) -> Self {
+        match self {
+            ParallelEdges::Allow => ParallelEdges::Allow,
+            ParallelEdges::Deny => ParallelEdges::Deny,
+            ParallelEdges::Merge(combine) => ParallelEdges::Merge(Arc::clone(combine)),
+        }
+    }
+}
+
+impl<E> fmt::Debug for ParallelEdges<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParallelEdges::Allow => f.write_str("Allow"),
+            ParallelEdges::Deny => f.write_str("Deny"),
+            ParallelEdges::Merge(_) => f.write_str("Merge(..)"),
+        }
+    }
+}
+
+/// The kind of graph a [`Graph`](crate::Graph) is meant to be, which it enforces on every edge
+/// insertion. The default policy allows both parallel edges and self loops.
+#[derive(Debug, Clone)]
+pub struct Policy<E> {
+    parallel_edges: ParallelEdges<E>,
+    self_loops: bool,
+}
+
+impl<E> Default for Policy<E> {
+    fn default() -> Self {
+        Self::multigraph()
+    }
+}
+
+impl<E> Policy<E> {
+    /// Allows both parallel edges and self loops.
+    pub fn multigraph() -> Self {
+        Self {
+            parallel_edges: ParallelEdges::Allow,
+            self_loops: true,
+        }
+    }
+
+    /// Denies both parallel edges and self loops.
+    pub fn simple() -> Self {
+        Self {
+            parallel_edges: ParallelEdges::Deny,
+            self_loops: false,
+        }
+    }
+
+    /// Sets what happens to parallel edges.
+    pub fn parallel_edges(mut self, parallel_edges: ParallelEdges<E>) -> Self {
+        self.parallel_edges = parallel_edges;
+        self
+    }
+
+    /// Merges parallel edges with `combine`, see [`ParallelEdges::Merge`].
+    pub fn merge_parallel_edges<F>(self, combine: F) -> Self
+    where
+        F: Fn(&mut E, E) + Send + Sync + 'static,
+    {
+        self.parallel_edges(ParallelEdges::Merge(Arc::new(combine)))
+    }
+
+    /// Sets whether an edge may start and end at the same vertex.
+    pub fn self_loops(mut self, allow: bool) -> Self {
+        self.self_loops = allow;
+        self
+    }
+
+    /// What happens to parallel edges.
+    pub fn parallel_edges_policy(&self) -> &ParallelEdges<E> {
+        &self.parallel_edges
+    }
+
+    /// Whether self loops are allowed.
+    pub fn allows_self_loops(&self) -> bool {
+        self.self_loops
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{
+    dfs_visit,
+    visit::{Control, GraphBase, InEdges, NodeIndexable, Nodes, OutEdges},
+    DfsEvent, EdgeWeight, Graph, VertexId,
+};
+
+/// The strongly connected components of a graph: the largest sets of vertices that can all reach
+/// each other. See [`tarjan_scc`] and [`kosaraju_scc`].
+pub struct Components<'a, G: GraphBase> {
+    graph: &'a G,
+    component: Vec<Option<usize>>,
+    members: Vec<Vec<G::NodeId>>,
+}
+
+impl<'a, G: NodeIndexable> Components<'a, G> {
+    fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            component: vec![None; graph.node_bound()],
+            members: vec![],
+        }
+    }
+
+    /// Makes a new component of the vertices `members`.
+    fn push(&mut self, mut members: Vec<G::NodeId>) {
+        for &id in &members {
+            self.component[self.graph.to_index(id)] = Some(self.members.len());
+        }
+        members.sort_unstable_by_key(|&id| self.graph.to_index(id));
+        self.members.push(members);
+    }
+
+    /// The number of components.
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// The id of the component of `id`, below [`count`](Components::count), or `None` if `id` is
+    /// not a vertex of the graph.
+    pub fn component(&self, id: G::NodeId) -> Option<usize> {
+        *self.component.get(self.graph.to_index(id))?
+    }
+
+    /// The vertices of every component, by component id.
+    pub fn members(&self) -> &[Vec<G::NodeId>] {
+        &self.members
+    }
+}
+
+/// The state of Tarjan's algorithm: the order in which the vertices were reached, the smallest
+/// order reachable from each through its descendants, and the vertices not yet in a component.
+struct Tarjan<N> {
+    order: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<N>,
+    next: usize,
+}
+
+impl<N: Copy> Tarjan<N> {
+    fn visit<G: NodeIndexable<NodeId = N>>(&mut self, graph: &G, id: N) {
+        let index = graph.to_index(id);
+        self.order[index] = Some(self.next);
+        self.low_link[index] = self.next;
+        self.on_stack[index] = true;
+        self.stack.push(id);
+        self.next += 1;
+    }
+}
+
+/// Finds the strongly connected components with Tarjan's algorithm, run iteratively. The
+/// components are numbered in reverse topological order: no edge goes from a component to one
+/// with a greater id.
+pub fn tarjan_scc<G>(graph: &G) -> Components<'_, G>
+where
+    G: Nodes + NodeIndexable + OutEdges,
+{
+    let mut components = Components::new(graph);
+    let mut state = Tarjan {
+        order: vec![None; graph.node_bound()],
+        low_link: vec![0; graph.node_bound()],
+        on_stack: vec![false; graph.node_bound()],
+        stack: vec![],
+        next: 0,
+    };
+
+    for root in graph.node_ids() {
+        if state.order[graph.to_index(root)].is_some() {
+            continue;
+        }
+        state.visit(graph, root);
+        // The vertices the search is in, with the neighbors left to look at.
+        let mut calls = vec![(root, graph.out_neighbors(root))];
+
+        while let Some((v, neighbors)) = calls.last_mut() {
+            let (v, next) = (*v, neighbors.next());
+            let vi = graph.to_index(v);
+            match next {
+                Some(w) => {
+                    let wi = graph.to_index(w);
+                    match state.order[wi] {
+                        None => {
+                            state.visit(graph, w);
+                            calls.push((w, graph.out_neighbors(w)));
+                        }
+                        Some(w_order) if state.on_stack[wi] => {
+                            state.low_link[vi] = state.low_link[vi].min(w_order);
+                        }
+                        Some(_) => {}
+                    }
+                }
+                None => {
+                    calls.pop();
+                    if Some(state.low_link[vi]) == state.order[vi] {
+                        let mut members = vec![];
+                        loop {
+                            let w = state.stack.pop().expect("v is on the stack");
+                            state.on_stack[graph.to_index(w)] = false;
+                            members.push(w);
+                            if w == v {
+                                break;
+                            }
+                        }
+                        components.push(members);
+                    }
+                    if let Some((parent, _)) = calls.last() {
+                        let pi = graph.to_index(*parent);
+                        state.low_link[pi] = state.low_link[pi].min(state.low_link[vi]);
+                    }
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Finds the strongly connected components with Kosaraju's algorithm: a depth-first search over
+/// the graph, then searches against the edges from the vertices it finished last. The components
+/// are numbered in topological order: no edge goes from a component to one with a smaller id.
+pub fn kosaraju_scc<G>(graph: &G) -> Components<'_, G>
+where
+    G: Nodes + NodeIndexable + OutEdges + InEdges,
+{
+    let mut finish_order = vec![];
+    dfs_visit(graph, graph.node_ids(), |event| {
+        if let DfsEvent::Finish(id, _) = event {
+            finish_order.push(id);
+        }
+        Control::Continue
+    });
+
+    let mut components = Components::new(graph);
+    let mut assigned = vec![false; graph.node_bound()];
+    for &root in finish_order.iter().rev() {
+        if assigned[graph.to_index(root)] {
+            continue;
+        }
+        assigned[graph.to_index(root)] = true;
+        let mut members = vec![];
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            members.push(id);
+            for from in graph.in_neighbors(id) {
+                if !assigned[graph.to_index(from)] {
+                    assigned[graph.to_index(from)] = true;
+                    stack.push(from);
+                }
+            }
+        }
+        components.push(members);
+    }
+    components
+}
+
+impl<T, E: EdgeWeight> Graph<T, E> {
+    /// Collapses every strongly connected component into a single vertex holding the ids of its
+    /// vertices, which gives a directed acyclic graph. The vertices of the condensation are
+    /// created in topological order, and the weights of all the edges from a component to
+    /// another are summed into a single edge; the edges within a component are dropped.
+    pub fn condensation(&self) -> Graph<Vec<VertexId>, E::Weight> {
+        let components = tarjan_scc(self);
+        let count = components.count();
+        let mut dag = Graph::new();
+        // Tarjan's components come in reverse topological order.
+        let ids: Vec<_> = components
+            .members()
+            .iter()
+            .rev()
+            .map(|members| dag.create_node(members.clone()))
+            .collect();
+        let vertex = |id| ids[count - 1 - components.component(id).expect("a live vertex")];
+
+        for (from, to, data) in self.edges() {
+            let (from, to) = (vertex(from), vertex(to));
+            if from == to {
+                continue;
+            }
+            let existing = dag.find_edges(from, to).expect("live vertices").next();
+            match existing {
+                Some(edge) => {
+                    let weight = dag.edge_weight(edge).expect("a live edge");
+                    dag.set_weight(edge, weight + data.weight())
+                        .expect("a live edge");
+                }
+                None => {
+                    dag.add_weighted_edge(from, to, data.weight())
+                        .expect("the default policy accepts any edge");
+                }
+            }
+        }
+        dag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graph, toposort};
+
+    #[test]
+    fn tarjan_and_kosaraju_agree() {
+        let (g, ids) = graph! {
+            "a" -> "b" : 1, "b" -> "c" : 1, "c" -> "a" : 1, "c" -> "d" : 2, "d" -> "e" : 1,
+            "e" -> "d" : 1, "b" -> "e" : 3, "f" -> "f" : 1, "a" -> "f" : 4, "e" -> "f" : 5
+        };
+        let (a, b, c, d, e, f) = (ids["a"], ids["b"], ids["c"], ids["d"], ids["e"], ids["f"]);
+
+        let tarjan = tarjan_scc(&g);
+        assert_eq!(tarjan.members(), &[vec![f], vec![d, e], vec![a, b, c]]);
+        assert_eq!(tarjan.component(e), Some(1));
+
+        let kosaraju = kosaraju_scc(&g);
+        assert_eq!(kosaraju.members(), &[vec![a, b, c], vec![d, e], vec![f]]);
+        assert_eq!(kosaraju.count(), 3);
+        assert_eq!(kosaraju.component(b), Some(0));
+    }
+
+    #[test]
+    fn condensation_is_a_dag() {
+        let (g, ids) = graph! {
+            "a" -> "b" : 1, "b" -> "a" : 1, "b" -> "c" : 2, "a" -> "c" : 3, "c" -> "d" : 4,
+            "d" -> "c" : 5, "a" -> "d" : 6
+        };
+        let dag = g.condensation();
+        let (top, bottom) = (dag.vertex_id(0).unwrap(), dag.vertex_id(1).unwrap());
+        assert_eq!(dag[top], vec![ids["a"], ids["b"]]);
+        assert_eq!(dag[bottom], vec![ids["c"], ids["d"]]);
+        assert_eq!(dag.edges().collect::<Vec<_>>(), vec![(top, bottom, &11)]);
+        assert_eq!(toposort(&dag).unwrap(), vec![top, bottom]);
+        assert!(dag.dot().contains("0 -> 1 [ ]"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{EdgeType, Graph, GraphError, Vertex, VertexId};
+
+/// The serialized form of a [`Graph`]. The slots of the removed vertices and edges are kept as
+/// `None`, so that the ids stay valid across a round trip. The policy holds closures and is left
+/// out: a deserialized graph has the default policy.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Graph")]
+struct Repr<V, W> {
+    directed: bool,
+    generation: u64,
+    vertices: Vec<Option<VertexRepr<V>>>,
+    edges: Vec<Option<EdgeRepr<W>>>,
+}
+
+/// A vertex; its index is its position in [`Repr::vertices`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Vertex")]
+struct VertexRepr<V> {
+    generation: u64,
+    data: V,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Edge")]
+struct EdgeRepr<W> {
+    from: VertexId,
+    to: VertexId,
+    data: W,
+}
+
+/// Why a serialized graph was rejected.
+struct Invalid(String);
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid graph: {}", self.0)
+    }
+}
+
+impl<T, E, Ty> Serialize for Graph<T, E, Ty>
+where
+    T: Serialize,
+    E: Serialize,
+    Ty: EdgeType,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let vertices = self.vertices.iter().map(|vx| {
+            vx.as_ref().map(|vx| VertexRepr {
+                generation: vx.id.generation,
+                data: &vx.data,
+            })
+        });
+        let edges = self.edges.iter().map(|edge| {
+            edge.as_ref().map(|edge| EdgeRepr {
+                from: edge.from,
+                to: edge.to,
+                data: &edge.data,
+            })
+        });
+        Repr {
+            directed: Ty::is_directed(),
+            generation: self.generation,
+            vertices: vertices.collect(),
+            edges: edges.collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializing checks that the graph is consistent: every edge has to join live vertices, with
+/// handles of the right generation, so that the adjacency lists never refer to a missing vertex.
+impl<'de, T, E, Ty> Deserialize<'de> for Graph<T, E, Ty>
+where
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+    Ty: EdgeType,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = Repr::<T, E>::deserialize(deserializer)?;
+        Graph::from_repr(repr).map_err(D::Error::custom)
+    }
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    fn from_repr(repr: Repr<T, E>) -> Result<Self, Invalid> {
+        if repr.directed != Ty::is_directed() {
+            let kind = |directed| if directed { "directed" } else { "undirected" };
+            return Err(Invalid(format!(
+                "expected an {} graph, found a {} one",
+                kind(Ty::is_directed()),
+                kind(repr.directed)
+            )));
+        }
+
+        let mut graph = Graph {
+            generation: repr.generation,
+            ..Graph::default()
+        };
+        for (index, vx) in repr.vertices.into_iter().enumerate() {
+            let vx = match vx {
+                Some(vx) if vx.generation > repr.generation => {
+                    return Err(Invalid(format!(
+                        "vertex {} is from generation {}, after the graph's {}",
+                        index, vx.generation, repr.generation
+                    )))
+                }
+                Some(vx) => {
+                    let id = VertexId {
+                        index,
+                        generation: vx.generation,
+                    };
+                    graph.node_count += 1;
+                    Some(Vertex::new(id, vx.data))
+                }
+                None => None,
+            };
+            graph.vertices.push(vx);
+        }
+
+        graph.edges = (0..repr.edges.len()).map(|_| None).collect();
+        for (id, edge) in repr.edges.into_iter().enumerate() {
+            if let Some(edge) = edge {
+                for end in [edge.from, edge.to] {
+                    graph
+                        .vertex(end)
+                        .map_err(|err: GraphError| Invalid(format!("edge {}: {}", id, err)))?;
+                }
+                graph.link(id, edge.from, edge.to, edge.data);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph, Graph, Undirected};
+
+    #[test]
+    fn serde_round_trip() {
+        let (mut g, ids) =
+            graph! { "a" -> "b" : 1, "b" -> "c" : 2, "c" -> "a" : 3, "a" -> "d" : 4 };
+        g.remove_vertex(ids["b"]).unwrap();
+        g.vacuum();
+        let (c, d) = (g.vertex_id(1).unwrap(), g.vertex_id(2).unwrap());
+        let e = g.create_node("e");
+        g.add_weighted_edge(c, e, 5).unwrap();
+        g.remove_edge(0).unwrap();
+        g.remove_vertex(d).unwrap();
+
+        let json = serde_json::to_string(&g).unwrap();
+        let back: Graph<String, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        assert_eq!((back.node_count(), back.edge_count()), (3, 1));
+        assert_eq!(back.generation(), 1);
+        assert_eq!(back[e], "e");
+        assert_eq!(back.neighbors(c).unwrap().collect::<Vec<_>>(), vec![e]);
+        assert_eq!(back.predecessors(e).unwrap().collect::<Vec<_>>(), vec![c]);
+    }
+
+    #[test]
+    fn dangling_edges_are_rejected() {
+        let json = r#"{"directed":true,"generation":0,
+            "vertices":[{"generation":0,"data":"a"},null],
+            "edges":[{"from":{"index":0,"generation":0},"to":{"index":1,"generation":0},"data":1}]}"#;
+        let err = serde_json::from_str::<Graph<String>>(json).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid graph: edge 0: vertex 1@0 has been removed"));
+
+        let err = serde_json::from_str::<Graph<String, i32, Undirected>>(
+            &json.replace("\"to\":{\"index\":1", "\"to\":{\"index\":0"),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid graph: expected an undirected graph"));
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    error::Error,
+    fmt,
+};
+
+use crate::{
+    depth_first_search,
+    visit::{GraphBase, InEdges, NodeIndexable, Nodes, OutEdges},
+};
+
+/// The error of a topological sort: a cycle of the graph, as the list of its vertices. Every
+/// vertex has an edge to the next one, and the last one has an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N> Cycle<N> {
+    /// The vertices on the cycle, in order.
+    pub fn vertices(&self) -> &[N] {
+        &self.0
+    }
+}
+
+impl<N: fmt::Debug> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the graph has a cycle: ")?;
+        for vertex in &self.0 {
+            write!(f, "{:?} -> ", vertex)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{:?}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: fmt::Debug> Error for Cycle<N> {}
+
+/// The vertices ready to be output by Kahn's algorithm.
+trait Ready<N> {
+    fn push(&mut self, id: N, index: usize);
+    fn pop(&mut self) -> Option<N>;
+}
+
+impl<N> Ready<N> for VecDeque<N> {
+    fn push(&mut self, id: N, _: usize) {
+        self.push_back(id);
+    }
+
+    fn pop(&mut self) -> Option<N> {
+        self.pop_front()
+    }
+}
+
+/// The ready vertices by their key, then by their index.
+struct ByKey<'a, G: GraphBase, K, F> {
+    graph: &'a G,
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+    key: F,
+}
+
+impl<G, K, F> Ready<G::NodeId> for ByKey<'_, G, K, F>
+where
+    G: NodeIndexable,
+    K: Ord,
+    F: FnMut(G::NodeId) -> K,
+{
+    fn push(&mut self, id: G::NodeId, index: usize) {
+        self.heap.push(Reverse(((self.key)(id), index)));
+    }
+
+    fn pop(&mut self) -> Option<G::NodeId> {
+        let Reverse((_, index)) = self.heap.pop()?;
+        self.graph.node_at(index)
+    }
+}
+
+/// Sorts the vertices of a directed graph so that every edge goes from a vertex to a later one,
+/// with Kahn's algorithm: the vertices without incoming edges come first, in the order of
+/// [`Nodes::node_ids`], then the ones whose predecessors have all been output, and so on. Fails
+/// with one of the cycles standing in the way if there is no such order.
+pub fn toposort<G>(graph: &G) -> Result<Vec<G::NodeId>, Cycle<G::NodeId>>
+where
+    G: Nodes + NodeIndexable + OutEdges + InEdges,
+{
+    kahn(graph, VecDeque::new())
+}
+
+/// Like [`toposort`], but whenever several vertices could come next, the one with the smallest
+/// key comes first, then the one with the smallest index. Keying the vertices by their id or
+/// their data gives the lexicographically smallest order; the key is computed once per vertex.
+pub fn toposort_by_key<G, K, F>(graph: &G, key: F) -> Result<Vec<G::NodeId>, Cycle<G::NodeId>>
+where
+    G: Nodes + NodeIndexable + OutEdges + InEdges,
+    K: Ord,
+    F: FnMut(G::NodeId) -> K,
+{
+    let ready = ByKey {
+        graph,
+        heap: BinaryHeap::new(),
+        key,
+    };
+    kahn(graph, ready)
+}
+
+/// Sorts the vertices of a directed graph like [`toposort`], but by running a depth-first search
+/// over the whole graph and reversing the order in which it is done with the vertices. Fails
+/// with the cycle closed by the first back edge the search meets.
+pub fn toposort_dfs<G>(graph: &G) -> Result<Vec<G::NodeId>, Cycle<G::NodeId>>
+where
+    G: Nodes + NodeIndexable + OutEdges,
+{
+    assert!(graph.is_directed(), "only directed graphs can be sorted");
+    let dfs = depth_first_search(graph, graph.node_ids());
+    if let Some(edge) = dfs.back_edges().next() {
+        return Err(Cycle(dfs.cycle(edge)));
+    }
+    Ok(dfs.finish_order().iter().rev().copied().collect())
+}
+
+fn kahn<G, R>(graph: &G, mut ready: R) -> Result<Vec<G::NodeId>, Cycle<G::NodeId>>
+where
+    G: Nodes + NodeIndexable + OutEdges + InEdges,
+    R: Ready<G::NodeId>,
+{
+    assert!(graph.is_directed(), "only directed graphs can be sorted");
+    let mut in_degree = vec![0; graph.node_bound()];
+    for id in graph.node_ids() {
+        let index = graph.to_index(id);
+        in_degree[index] = graph.in_edge_refs(id).count();
+        if in_degree[index] == 0 {
+            ready.push(id, index);
+        }
+    }
+
+    let mut order = vec![];
+    while let Some(id) = ready.pop() {
+        order.push(id);
+        for to in graph.out_neighbors(id) {
+            let index = graph.to_index(to);
+            in_degree[index] -= 1;
+            if in_degree[index] == 0 {
+                ready.push(to, index);
+            }
+        }
+    }
+
+    match graph
+        .node_ids()
+        .find(|&id| in_degree[graph.to_index(id)] > 0)
+    {
+        Some(stuck) => Err(cycle_among_stuck(graph, &in_degree, stuck)),
+        None => Ok(order),
+    }
+}
+
+/// Finds a cycle among the vertices left with incoming edges by Kahn's algorithm. Each of them has
+/// a predecessor that was left too, so walking back from one of them has to loop.
+fn cycle_among_stuck<G>(graph: &G, in_degree: &[usize], start: G::NodeId) -> Cycle<G::NodeId>
+where
+    G: NodeIndexable + InEdges,
+{
+    let mut position = vec![None; graph.node_bound()];
+    let mut path = vec![];
+    let mut at = start;
+    while position[graph.to_index(at)].is_none() {
+        position[graph.to_index(at)] = Some(path.len());
+        path.push(at);
+        at = graph
+            .in_neighbors(at)
+            .find(|&from| in_degree[graph.to_index(from)] > 0)
+            .expect("a vertex left by Kahn's algorithm has a predecessor left too");
+    }
+    let mut cycle = path.split_off(position[graph.to_index(at)].expect("visited"));
+    cycle.reverse();
+    Cycle(cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    #[test]
+    fn toposort_orders_a_dag() {
+        let (g, ids) = graph! {
+            "shirt" -> "tie", "tie" -> "jacket", "pants" -> "shoes", "pants" -> "belt",
+            "belt" -> "jacket", "shirt" -> "belt", "socks" -> "shoes"
+        };
+        let names = |order: Vec<_>| order.into_iter().map(|id| g[id]).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(toposort(&g).unwrap()),
+            vec!["shirt", "pants", "socks", "tie", "belt", "shoes", "jacket"]
+        );
+        assert_eq!(
+            names(toposort_by_key(&g, |id| g[id]).unwrap()),
+            vec!["pants", "shirt", "belt", "socks", "shoes", "tie", "jacket"]
+        );
+        let dfs = toposort_dfs(&g).unwrap();
+        let position = |name| dfs.iter().position(|&id| id == ids[name]).unwrap();
+        for (from, to, _) in g.edges() {
+            assert!(position(g[from]) < position(g[to]));
+        }
+    }
+
+    #[test]
+    fn toposort_reports_a_cycle() {
+        let (g, ids) = graph! {
+            "a" -> "b", "b" -> "c", "c" -> "d", "d" -> "b", "c" -> "e"
+        };
+        let cycle = vec![ids["b"], ids["c"], ids["d"]];
+        let err = toposort(&g).unwrap_err();
+        assert_eq!(err.vertices().len(), 3);
+        assert!(cycle.iter().all(|id| err.vertices().contains(id)));
+        assert_eq!(toposort_dfs(&g), Err(Cycle(cycle)));
+        assert_eq!(
+            toposort_by_key(&g, |id| id).unwrap_err().to_string(),
+            format!(
+                "the graph has a cycle: {:?} -> {:?} -> {:?} -> {:?}",
+                ids["c"], ids["d"], ids["b"], ids["c"]
+            )
+        );
+    }
+}
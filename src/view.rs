@@ -0,0 +1,651 @@
+use std::{iter::Enumerate, ops::Index, slice};
+
+use crate::{
+    visit::{EdgeRef, EdgeWeights, GraphBase, InEdges, NodeCount, NodeIndexable, Nodes, OutEdges},
+    AdjacentEdges, Edge, EdgeId, EdgeRecord, EdgeType, EdgeWeight, Edges, Graph, GraphError,
+    Neighbors, Vertex, VertexId, VertexIds, Vertices,
+};
+
+/// Decides which vertices and edges of a [`Graph`] a [`FilteredGraph`] shows. An edge is only
+/// shown if both of its endpoints are.
+pub trait ViewFilter<T, E> {
+    fn keep_vertex(&self, id: VertexId, data: &T) -> bool;
+    fn keep_edge(&self, id: EdgeId, data: &E) -> bool;
+}
+
+/// Shows the vertices for which the predicate holds, and all the edges between them.
+pub struct VertexFilter<P>(pub P);
+
+/// Shows all the vertices, and the edges for which the predicate holds.
+pub struct EdgeFilter<P>(pub P);
+
+/// Shows the vertices for which the first predicate holds, and the edges between them for which
+/// the second one holds.
+pub struct Filter<VP, EP>(pub VP, pub EP);
+
+impl<T, E, P: Fn(VertexId, &T) -> bool> ViewFilter<T, E> for VertexFilter<P> {
+    fn keep_vertex(&self, id: VertexId, data: &T) -> bool {
+        (self.0)(id, data)
+    }
+
+    fn keep_edge(&self, _: EdgeId, _: &E) -> bool {
+        true
+    }
+}
+
+impl<T, E, P: Fn(EdgeId, &E) -> bool> ViewFilter<T, E> for EdgeFilter<P> {
+    fn keep_vertex(&self, _: VertexId, _: &T) -> bool {
+        true
+    }
+
+    fn keep_edge(&self, id: EdgeId, data: &E) -> bool {
+        (self.0)(id, data)
+    }
+}
+
+impl<T, E, VP, EP> ViewFilter<T, E> for Filter<VP, EP>
+where
+    VP: Fn(VertexId, &T) -> bool,
+    EP: Fn(EdgeId, &E) -> bool,
+{
+    fn keep_vertex(&self, id: VertexId, data: &T) -> bool {
+        (self.0)(id, data)
+    }
+
+    fn keep_edge(&self, id: EdgeId, data: &E) -> bool {
+        (self.1)(id, data)
+    }
+}
+
+/// A view of a [`Graph`] that hides the vertices and edges rejected by a [`ViewFilter`], without
+/// copying anything. It offers the same queries as the graph; hidden vertices are reported as
+/// [`GraphError::HiddenVertex`].
+pub struct FilteredGraph<'a, T, E, Ty, F> {
+    graph: &'a Graph<T, E, Ty>,
+    filter: F,
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    /// A view of the graph without the vertices for which `keep` does not hold, nor their edges.
+    pub fn filter_vertices<P>(&self, keep: P) -> FilteredGraph<'_, T, E, Ty, VertexFilter<P>>
+    where
+        P: Fn(VertexId, &T) -> bool,
+    {
+        FilteredGraph::new(self, VertexFilter(keep))
+    }
+
+    /// A view of the graph without the edges for which `keep` does not hold.
+    pub fn filter_edges<P>(&self, keep: P) -> FilteredGraph<'_, T, E, Ty, EdgeFilter<P>>
+    where
+        P: Fn(EdgeId, &E) -> bool,
+    {
+        FilteredGraph::new(self, EdgeFilter(keep))
+    }
+}
+
+impl<'a, T, E, Ty, F> FilteredGraph<'a, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    /// A view of `graph` showing what `filter` keeps.
+    pub fn new(graph: &'a Graph<T, E, Ty>, filter: F) -> Self {
+        Self { graph, filter }
+    }
+
+    /// The underlying graph.
+    pub fn graph(&self) -> &'a Graph<T, E, Ty> {
+        self.graph
+    }
+
+    /// Whether the vertex `id` is a live vertex of the graph that the view shows.
+    pub fn contains_vertex(&self, id: VertexId) -> bool {
+        self.get(id).is_ok()
+    }
+
+    /// Whether the edge `id` is a live edge of the graph that the view shows.
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        match self.graph.edges.get(id) {
+            Some(Some(edge)) => self.keeps_edge(id, edge),
+            _ => false,
+        }
+    }
+
+    /// Returns the data stored in the vertex `id`.
+    pub fn get(&self, id: VertexId) -> Result<&'a T, GraphError> {
+        let data = self.graph.get(id)?;
+        if self.filter.keep_vertex(id, data) {
+            Ok(data)
+        } else {
+            Err(GraphError::HiddenVertex(id))
+        }
+    }
+
+    /// The number of vertices the view shows. This walks over all the vertices of the graph.
+    pub fn node_count(&self) -> usize {
+        self.vertices().count()
+    }
+
+    /// The number of edges the view shows. This walks over all the edges of the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    /// See [`Graph::vertices`].
+    pub fn vertices(&self) -> FilteredVertices<'_, T, E, Ty, F> {
+        FilteredVertices {
+            view: self,
+            iter: self.graph.vertices(),
+        }
+    }
+
+    /// See [`Graph::vertex_ids`].
+    pub fn vertex_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertices().map(|(id, _)| id)
+    }
+
+    /// See [`Graph::edges`].
+    pub fn edges(&self) -> FilteredEdges<'_, T, E, Ty, F> {
+        FilteredEdges {
+            view: self,
+            iter: self.graph.edges.iter().enumerate(),
+        }
+    }
+
+    /// See [`Graph::neighbors`].
+    pub fn neighbors(
+        &self,
+        id: VertexId,
+    ) -> Result<FilteredNeighbors<'_, T, E, Ty, F>, GraphError> {
+        Ok(FilteredNeighbors {
+            iter: self.out_edges(id)?,
+        })
+    }
+
+    /// See [`Graph::out_edges`].
+    pub fn out_edges(
+        &self,
+        id: VertexId,
+    ) -> Result<FilteredAdjacentEdges<'_, T, E, Ty, F>, GraphError> {
+        self.get(id)?;
+        Ok(FilteredAdjacentEdges {
+            view: self,
+            iter: self.graph.vertex(id)?.out_edges.iter(),
+        })
+    }
+
+    /// See [`Graph::out_degree`]. This walks over all the edges going out of `id`.
+    pub fn out_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        Ok(self.out_edges(id)?.count())
+    }
+
+    /// See [`Graph::predecessors`].
+    pub fn predecessors(
+        &self,
+        id: VertexId,
+    ) -> Result<FilteredNeighbors<'_, T, E, Ty, F>, GraphError> {
+        Ok(FilteredNeighbors {
+            iter: self.incoming_edges(id)?,
+        })
+    }
+
+    /// See [`Graph::incoming_edges`].
+    pub fn incoming_edges(
+        &self,
+        id: VertexId,
+    ) -> Result<FilteredAdjacentEdges<'_, T, E, Ty, F>, GraphError> {
+        self.get(id)?;
+        Ok(FilteredAdjacentEdges {
+            view: self,
+            iter: self.graph.vertex(id)?.in_edges.iter(),
+        })
+    }
+
+    /// See [`Graph::in_degree`]. This walks over all the edges coming into `id`.
+    pub fn in_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        Ok(self.incoming_edges(id)?.count())
+    }
+
+    /// The entries of one of the adjacency lists of `id` that the view shows, panicking if `id`
+    /// is not shown.
+    fn adjacency<'s>(
+        &'s self,
+        id: VertexId,
+        list: fn(&'a Vertex<T>) -> &'a [Edge],
+    ) -> impl Iterator<Item = &'a Edge> + 's {
+        if let Err(err) = self.get(id) {
+            panic!("{}", err);
+        }
+        let vx = self.graph.vertex(id).expect("the vertex is live");
+        list(vx).iter().filter(move |e| {
+            let edge = self.graph.edges[e.id]
+                .as_ref()
+                .expect("adjacency lists only hold live edges");
+            self.keeps_edge(e.id, edge)
+        })
+    }
+
+    fn keeps_vertex(&self, id: VertexId) -> bool {
+        self.contains_vertex(id)
+    }
+
+    fn keeps_edge(&self, id: EdgeId, edge: &EdgeRecord<E>) -> bool {
+        self.filter.keep_edge(id, &edge.data)
+            && self.keeps_vertex(edge.from)
+            && self.keeps_vertex(edge.to)
+    }
+}
+
+impl<T, E, Ty, F> Index<VertexId> for FilteredGraph<'_, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Output = T;
+
+    fn index(&self, id: VertexId) -> &T {
+        match self.get(id) {
+            Ok(data) => data,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T, E, Ty: EdgeType, F> GraphBase for FilteredGraph<'_, T, E, Ty, F> {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<T, E, Ty: EdgeType, F: ViewFilter<T, E>> NodeCount for FilteredGraph<'_, T, E, Ty, F> {
+    fn node_count(&self) -> usize {
+        self.vertices().count()
+    }
+}
+
+impl<T, E, Ty: EdgeType, F: ViewFilter<T, E>> NodeIndexable for FilteredGraph<'_, T, E, Ty, F> {
+    fn node_bound(&self) -> usize {
+        self.graph.node_bound()
+    }
+
+    fn to_index(&self, id: VertexId) -> usize {
+        id.index()
+    }
+
+    fn node_at(&self, index: usize) -> Option<VertexId> {
+        self.graph
+            .vertex_id(index)
+            .filter(|&id| self.keeps_vertex(id))
+    }
+}
+
+impl<T, E, Ty: EdgeType, F: ViewFilter<T, E>> Nodes for FilteredGraph<'_, T, E, Ty, F> {
+    fn node_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertex_ids()
+    }
+}
+
+impl<T, E, Ty: EdgeType, F: ViewFilter<T, E>> OutEdges for FilteredGraph<'_, T, E, Ty, F> {
+    fn out_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.adjacency(id, |vx| &vx.out_edges)
+            .map(move |e| EdgeRef {
+                id: e.id,
+                source: id,
+                target: e.other_id,
+            })
+    }
+}
+
+impl<T, E, Ty: EdgeType, F: ViewFilter<T, E>> InEdges for FilteredGraph<'_, T, E, Ty, F> {
+    fn in_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.adjacency(id, |vx| &vx.in_edges).map(move |e| EdgeRef {
+            id: e.id,
+            source: e.other_id,
+            target: id,
+        })
+    }
+}
+
+impl<T, E, Ty, F> EdgeWeights for FilteredGraph<'_, T, E, Ty, F>
+where
+    E: EdgeWeight,
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Weight = E::Weight;
+
+    fn weight_of(&self, id: EdgeId) -> Option<E::Weight> {
+        if self.contains_edge(id) {
+            self.graph.edge_weight(id).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator over the vertices shown by a [`FilteredGraph`], see [`FilteredGraph::vertices`].
+pub struct FilteredVertices<'v, T, E, Ty, F> {
+    view: &'v FilteredGraph<'v, T, E, Ty, F>,
+    iter: Vertices<'v, T>,
+}
+
+impl<'v, T, E, Ty, F> Iterator for FilteredVertices<'v, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Item = (VertexId, &'v T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = &self.view.filter;
+        self.iter.find(|&(id, data)| filter.keep_vertex(id, data))
+    }
+}
+
+/// An iterator over the edges shown by a [`FilteredGraph`], see [`FilteredGraph::edges`].
+pub struct FilteredEdges<'v, T, E, Ty, F> {
+    view: &'v FilteredGraph<'v, T, E, Ty, F>,
+    iter: Enumerate<slice::Iter<'v, Option<EdgeRecord<E>>>>,
+}
+
+impl<'v, T, E, Ty, F> Iterator for FilteredEdges<'v, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Item = (VertexId, VertexId, &'v E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (id, slot) in &mut self.iter {
+            if let Some(edge) = slot {
+                if self.view.keeps_edge(id, edge) {
+                    return Some((edge.from, edge.to, &edge.data));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the edges of a vertex shown by a [`FilteredGraph`], as `(other_id, &weight)`
+/// pairs. See [`FilteredGraph::out_edges`] and [`FilteredGraph::incoming_edges`].
+pub struct FilteredAdjacentEdges<'v, T, E, Ty, F> {
+    view: &'v FilteredGraph<'v, T, E, Ty, F>,
+    iter: slice::Iter<'v, Edge>,
+}
+
+impl<'v, T, E, Ty, F> Iterator for FilteredAdjacentEdges<'v, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Item = (VertexId, &'v E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.view.graph;
+        for e in &mut self.iter {
+            let edge = graph.edges[e.id]
+                .as_ref()
+                .expect("adjacency lists only hold live edges");
+            if self.view.keeps_edge(e.id, edge) {
+                return Some((e.other_id, &edge.data));
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the neighbors of a vertex shown by a [`FilteredGraph`], see
+/// [`FilteredGraph::neighbors`] and [`FilteredGraph::predecessors`].
+pub struct FilteredNeighbors<'v, T, E, Ty, F> {
+    iter: FilteredAdjacentEdges<'v, T, E, Ty, F>,
+}
+
+impl<'v, T, E, Ty, F> Iterator for FilteredNeighbors<'v, T, E, Ty, F>
+where
+    Ty: EdgeType,
+    F: ViewFilter<T, E>,
+{
+    type Item = VertexId;
+
+    fn next(&mut self) -> Option<VertexId> {
+        self.iter.next().map(|(id, _)| id)
+    }
+}
+
+/// The transpose of a [`Graph`]: every edge is seen pointing the other way. The view swaps the
+/// roles of the `in_edges` and `out_edges` lists of the vertices and does not copy anything. For
+/// an undirected graph it is the graph itself.
+pub struct Reversed<'a, T, E, Ty> {
+    graph: &'a Graph<T, E, Ty>,
+}
+
+impl<T, E, Ty: EdgeType> Graph<T, E, Ty> {
+    /// A view of the graph with all the edges reversed.
+    pub fn reversed(&self) -> Reversed<'_, T, E, Ty> {
+        Reversed { graph: self }
+    }
+}
+
+impl<'a, T, E, Ty: EdgeType> Reversed<'a, T, E, Ty> {
+    /// The underlying graph.
+    pub fn graph(&self) -> &'a Graph<T, E, Ty> {
+        self.graph
+    }
+
+    /// See [`Graph::get`].
+    pub fn get(&self, id: VertexId) -> Result<&'a T, GraphError> {
+        self.graph.get(id)
+    }
+
+    /// See [`Graph::node_count`].
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    /// See [`Graph::edge_count`].
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// See [`Graph::vertices`].
+    pub fn vertices(&self) -> Vertices<'a, T> {
+        self.graph.vertices()
+    }
+
+    /// See [`Graph::vertex_ids`].
+    pub fn vertex_ids(&self) -> VertexIds<'a, T> {
+        self.graph.vertex_ids()
+    }
+
+    /// Returns the live edges as `(to_id, from_id, &weight)` triples, in the order of their ids.
+    pub fn edges(&self) -> ReversedEdges<'a, E> {
+        ReversedEdges {
+            iter: self.graph.edges(),
+        }
+    }
+
+    /// Returns the endpoints of the edge `id`, reversed.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Result<(VertexId, VertexId), GraphError> {
+        self.graph.edge_endpoints(id).map(|(from, to)| (to, from))
+    }
+
+    /// See [`Graph::edge_data`].
+    pub fn edge_data(&self, id: EdgeId) -> Result<&'a E, GraphError> {
+        self.graph.edge_data(id)
+    }
+
+    /// The vertices that have an edge into `id` in the graph.
+    pub fn neighbors(&self, id: VertexId) -> Result<Neighbors<'a>, GraphError> {
+        self.graph.predecessors(id)
+    }
+
+    /// The edges coming into `id` in the graph, as `(from_id, &weight)` pairs.
+    pub fn out_edges(&self, id: VertexId) -> Result<AdjacentEdges<'a, E>, GraphError> {
+        self.graph.incoming_edges(id)
+    }
+
+    /// The number of edges coming into `id` in the graph.
+    pub fn out_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        self.graph.in_degree(id)
+    }
+
+    /// The vertices that `id` has an edge to in the graph.
+    pub fn predecessors(&self, id: VertexId) -> Result<Neighbors<'a>, GraphError> {
+        self.graph.neighbors(id)
+    }
+
+    /// The edges going out of `id` in the graph, as `(to_id, &weight)` pairs.
+    pub fn incoming_edges(&self, id: VertexId) -> Result<AdjacentEdges<'a, E>, GraphError> {
+        self.graph.out_edges(id)
+    }
+
+    /// The number of edges going out of `id` in the graph.
+    pub fn in_degree(&self, id: VertexId) -> Result<usize, GraphError> {
+        self.graph.out_degree(id)
+    }
+}
+
+impl<T, E, Ty> Clone for Reversed<'_, T, E, Ty> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E, Ty> Copy for Reversed<'_, T, E, Ty> {}
+
+impl<T, E, Ty: EdgeType> Index<VertexId> for Reversed<'_, T, E, Ty> {
+    type Output = T;
+
+    fn index(&self, id: VertexId) -> &T {
+        &self.graph[id]
+    }
+}
+
+impl<T, E, Ty: EdgeType> GraphBase for Reversed<'_, T, E, Ty> {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeCount for Reversed<'_, T, E, Ty> {
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeIndexable for Reversed<'_, T, E, Ty> {
+    fn node_bound(&self) -> usize {
+        self.graph.node_bound()
+    }
+
+    fn to_index(&self, id: VertexId) -> usize {
+        id.index()
+    }
+
+    fn node_at(&self, index: usize) -> Option<VertexId> {
+        self.graph.vertex_id(index)
+    }
+}
+
+impl<T, E, Ty: EdgeType> Nodes for Reversed<'_, T, E, Ty> {
+    fn node_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.graph.vertex_ids()
+    }
+}
+
+impl<T, E, Ty: EdgeType> OutEdges for Reversed<'_, T, E, Ty> {
+    fn out_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.graph.in_edge_refs(id).map(|e| EdgeRef {
+            id: e.id,
+            source: e.target,
+            target: e.source,
+        })
+    }
+}
+
+impl<T, E, Ty: EdgeType> InEdges for Reversed<'_, T, E, Ty> {
+    fn in_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        self.graph.out_edge_refs(id).map(|e| EdgeRef {
+            id: e.id,
+            source: e.target,
+            target: e.source,
+        })
+    }
+}
+
+impl<T, E: EdgeWeight, Ty: EdgeType> EdgeWeights for Reversed<'_, T, E, Ty> {
+    type Weight = E::Weight;
+
+    fn weight_of(&self, id: EdgeId) -> Option<E::Weight> {
+        self.graph.edge_weight(id).ok()
+    }
+}
+
+/// An iterator over the edges of a [`Reversed`] graph, see [`Reversed::edges`].
+pub struct ReversedEdges<'a, E> {
+    iter: Edges<'a, E>,
+}
+
+impl<'a, E> Iterator for ReversedEdges<'a, E> {
+    type Item = (VertexId, VertexId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(from, to, data)| (to, from, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{graph, Filter, FilteredGraph, GraphError};
+
+    #[test]
+    fn filtered_views() {
+        let (g, ids) = graph! { "a" -> "b" : 1, "b" -> "c" : 0, "a" -> "c" : 2, "c" -> "d" : 3 };
+        let (a, b, c, d) = (ids["a"], ids["b"], ids["c"], ids["d"]);
+
+        let up = g.filter_vertices(|_, &name| name != "c");
+        assert_eq!(up.vertex_ids().collect::<Vec<_>>(), vec![a, b, d]);
+        assert_eq!(up.edges().collect::<Vec<_>>(), vec![(a, b, &1)]);
+        assert_eq!(up.neighbors(a).unwrap().collect::<Vec<_>>(), vec![b]);
+        assert_eq!(up.in_degree(d), Ok(0));
+        assert_eq!(up.get(c), Err(GraphError::HiddenVertex(c)));
+        assert_eq!((up.node_count(), up.edge_count()), (3, 1));
+
+        let positive = g.filter_edges(|_, &wt| wt > 0);
+        assert_eq!(positive.neighbors(b).unwrap().count(), 0);
+        assert_eq!(
+            positive.predecessors(c).unwrap().collect::<Vec<_>>(),
+            vec![a]
+        );
+        assert_eq!(positive[d], "d");
+
+        let both = FilteredGraph::new(&g, Filter(|id, _: &&str| id != b, |_, &wt: &i32| wt < 3));
+        assert_eq!(both.edges().collect::<Vec<_>>(), vec![(a, c, &2)]);
+        assert_eq!(both.out_edges(c).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reversed_view() {
+        let (g, ids) = graph! { "a" -> "b" : 1, "a" -> "c" : 2, "c" -> "b" : 3 };
+        let (a, b, c) = (ids["a"], ids["b"], ids["c"]);
+        let rev = g.reversed();
+
+        assert_eq!(rev.neighbors(b).unwrap().collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(
+            rev.out_edges(b).unwrap().collect::<Vec<_>>(),
+            vec![(a, &1), (c, &3)]
+        );
+        assert_eq!(rev.predecessors(a).unwrap().collect::<Vec<_>>(), vec![b, c]);
+        assert_eq!((rev.out_degree(a), rev.in_degree(a)), (Ok(0), Ok(2)));
+        assert_eq!(rev.edges().next(), Some((b, a, &1)));
+        assert_eq!(rev.edge_endpoints(2), Ok((b, c)));
+        assert_eq!(rev[c], "c");
+    }
+}
@@ -0,0 +1,329 @@
+use std::{fmt::Debug, hash::Hash};
+
+use petgraph::{
+    graph::{EdgeIndex, IndexType, NodeIndex},
+    visit::EdgeRef as _,
+    Direction,
+};
+
+use crate::{EdgeId, EdgeType, EdgeWeight, Graph, Vertex, VertexId, Weight};
+
+/// An edge as seen by the traits of this module: its id and its endpoints. For an undirected
+/// graph, `source` is the node the edge was reached from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeRef<N, Ed> {
+    pub id: Ed,
+    pub source: N,
+    pub target: N,
+}
+
+/// What a traversal should do after a visitor has looked at an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Control {
+    /// Go on as usual.
+    Continue,
+    /// Do not go further from here: what gets pruned depends on the event.
+    Prune,
+    /// Stop the traversal right away.
+    Break,
+}
+
+/// The types used to identify the nodes and edges of a graph. This and the other graph traits
+/// abstract over the ways a graph can be stored, so that algorithms written against them run on a
+/// [`Graph`], on its views, or on a petgraph graph alike. The traits are read-only, and their
+/// methods taking a node panic if it is not a node of the graph.
+pub trait GraphBase {
+    type NodeId: Copy + Eq + Hash + Debug;
+    type EdgeId: Copy + Eq + Hash + Debug;
+
+    /// Whether the edges of the graph are directed.
+    fn is_directed(&self) -> bool;
+}
+
+/// Graphs that know how many nodes they have.
+pub trait NodeCount: GraphBase {
+    fn node_count(&self) -> usize;
+}
+
+/// Graphs whose nodes map to small indices, so that algorithms can keep per-node state in a
+/// plain vector instead of a hash map.
+pub trait NodeIndexable: GraphBase {
+    /// One more than the largest index of a node.
+    fn node_bound(&self) -> usize;
+
+    /// The index of the node `id`, smaller than [`node_bound`](NodeIndexable::node_bound).
+    fn to_index(&self, id: Self::NodeId) -> usize;
+
+    /// The node with the given index, if there is one.
+    fn node_at(&self, index: usize) -> Option<Self::NodeId>;
+}
+
+/// Graphs whose nodes can be enumerated.
+pub trait Nodes: GraphBase {
+    fn node_ids(&self) -> impl Iterator<Item = Self::NodeId> + '_;
+}
+
+/// Graphs whose edges can be followed forwards.
+pub trait OutEdges: GraphBase {
+    /// The edges going out of `id`, with `id` as their source.
+    fn out_edge_refs(
+        &self,
+        id: Self::NodeId,
+    ) -> impl Iterator<Item = EdgeRef<Self::NodeId, Self::EdgeId>> + '_;
+
+    /// The targets of the edges going out of `id`.
+    fn out_neighbors(&self, id: Self::NodeId) -> impl Iterator<Item = Self::NodeId> + '_ {
+        self.out_edge_refs(id).map(|e| e.target)
+    }
+}
+
+/// Graphs whose edges can be followed backwards.
+pub trait InEdges: GraphBase {
+    /// The edges coming into `id`, with `id` as their target.
+    fn in_edge_refs(
+        &self,
+        id: Self::NodeId,
+    ) -> impl Iterator<Item = EdgeRef<Self::NodeId, Self::EdgeId>> + '_;
+
+    /// The sources of the edges coming into `id`.
+    fn in_neighbors(&self, id: Self::NodeId) -> impl Iterator<Item = Self::NodeId> + '_ {
+        self.in_edge_refs(id).map(|e| e.source)
+    }
+}
+
+/// Graphs whose edges carry a [`Weight`].
+pub trait EdgeWeights: GraphBase {
+    type Weight: Weight;
+
+    /// The weight of the edge `id`, or `None` if it is not an edge of the graph.
+    fn weight_of(&self, id: Self::EdgeId) -> Option<Self::Weight>;
+}
+
+impl<T, E, Ty: EdgeType> GraphBase for Graph<T, E, Ty> {
+    type NodeId = VertexId;
+    type EdgeId = EdgeId;
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeCount for Graph<T, E, Ty> {
+    fn node_count(&self) -> usize {
+        self.node_count
+    }
+}
+
+impl<T, E, Ty: EdgeType> NodeIndexable for Graph<T, E, Ty> {
+    fn node_bound(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn to_index(&self, id: VertexId) -> usize {
+        id.index
+    }
+
+    fn node_at(&self, index: usize) -> Option<VertexId> {
+        self.vertex_id(index)
+    }
+}
+
+impl<T, E, Ty: EdgeType> Nodes for Graph<T, E, Ty> {
+    fn node_ids(&self) -> impl Iterator<Item = VertexId> + '_ {
+        self.vertex_ids()
+    }
+}
+
+impl<T, E, Ty: EdgeType> OutEdges for Graph<T, E, Ty> {
+    fn out_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        live(self, id).out_edges.iter().map(move |e| EdgeRef {
+            id: e.id,
+            source: id,
+            target: e.other_id,
+        })
+    }
+}
+
+impl<T, E, Ty: EdgeType> InEdges for Graph<T, E, Ty> {
+    fn in_edge_refs(&self, id: VertexId) -> impl Iterator<Item = EdgeRef<VertexId, EdgeId>> + '_ {
+        live(self, id).in_edges.iter().map(move |e| EdgeRef {
+            id: e.id,
+            source: e.other_id,
+            target: id,
+        })
+    }
+}
+
+impl<T, E: EdgeWeight, Ty: EdgeType> EdgeWeights for Graph<T, E, Ty> {
+    type Weight = E::Weight;
+
+    fn weight_of(&self, id: EdgeId) -> Option<E::Weight> {
+        self.edge_weight(id).ok()
+    }
+}
+
+fn live<T, E, Ty: EdgeType>(graph: &Graph<T, E, Ty>, id: VertexId) -> &Vertex<T> {
+    match graph.vertex(id) {
+        Ok(vx) => vx,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+impl<N, E, Ty, Ix> GraphBase for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    type NodeId = NodeIndex<Ix>;
+    type EdgeId = EdgeIndex<Ix>;
+
+    fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+}
+
+impl<N, E, Ty, Ix> NodeCount for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    fn node_count(&self) -> usize {
+        self.node_count()
+    }
+}
+
+impl<N, E, Ty, Ix> NodeIndexable for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    fn node_bound(&self) -> usize {
+        self.node_count()
+    }
+
+    fn to_index(&self, id: NodeIndex<Ix>) -> usize {
+        id.index()
+    }
+
+    fn node_at(&self, index: usize) -> Option<NodeIndex<Ix>> {
+        if index < self.node_count() {
+            Some(NodeIndex::new(index))
+        } else {
+            None
+        }
+    }
+}
+
+impl<N, E, Ty, Ix> Nodes for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    fn node_ids(&self) -> impl Iterator<Item = NodeIndex<Ix>> + '_ {
+        self.node_indices()
+    }
+}
+
+impl<N, E, Ty, Ix> OutEdges for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    fn out_edge_refs(
+        &self,
+        id: NodeIndex<Ix>,
+    ) -> impl Iterator<Item = EdgeRef<NodeIndex<Ix>, EdgeIndex<Ix>>> + '_ {
+        assert!(id.index() < self.node_count(), "unknown node {:?}", id);
+        self.edges_directed(id, Direction::Outgoing)
+            .map(|e| EdgeRef {
+                id: e.id(),
+                source: e.source(),
+                target: e.target(),
+            })
+    }
+}
+
+impl<N, E, Ty, Ix> InEdges for petgraph::Graph<N, E, Ty, Ix>
+where
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    fn in_edge_refs(
+        &self,
+        id: NodeIndex<Ix>,
+    ) -> impl Iterator<Item = EdgeRef<NodeIndex<Ix>, EdgeIndex<Ix>>> + '_ {
+        assert!(id.index() < self.node_count(), "unknown node {:?}", id);
+        self.edges_directed(id, Direction::Incoming)
+            .map(|e| EdgeRef {
+                id: e.id(),
+                source: e.source(),
+                target: e.target(),
+            })
+    }
+}
+
+impl<N, E, Ty, Ix> EdgeWeights for petgraph::Graph<N, E, Ty, Ix>
+where
+    E: EdgeWeight,
+    Ty: petgraph::EdgeType,
+    Ix: IndexType,
+{
+    type Weight = E::Weight;
+
+    fn weight_of(&self, id: EdgeIndex<Ix>) -> Option<E::Weight> {
+        self.edge_weight(id).map(|e| e.weight())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph;
+
+    /// The nodes reachable from `start`, by index, with the total weight of the edges out of each.
+    fn reach<G>(g: &G, start: G::NodeId) -> Vec<(usize, G::Weight)>
+    where
+        G: NodeIndexable + OutEdges + EdgeWeights,
+    {
+        let mut seen = vec![false; g.node_bound()];
+        let mut stack = vec![start];
+        let mut out = vec![];
+        seen[g.to_index(start)] = true;
+        while let Some(id) = stack.pop() {
+            let mut total = G::Weight::zero();
+            for e in g.out_edge_refs(id) {
+                total = total + g.weight_of(e.id).unwrap();
+                if !seen[g.to_index(e.target)] {
+                    seen[g.to_index(e.target)] = true;
+                    stack.push(e.target);
+                }
+            }
+            out.push((g.to_index(id), total));
+        }
+        out.sort_unstable();
+        out
+    }
+
+    #[test]
+    fn algorithms_run_on_any_representation() {
+        let (g, ids) = graph! { "a" -> "b" : 1, "b" -> "c" : 2, "a" -> "c" : 4, "d" -> "a" : 8 };
+        let (a, c) = (ids["a"], ids["c"]);
+        assert_eq!(reach(&g, a), vec![(0, 5), (1, 2), (2, 0)]);
+        assert_eq!(
+            reach(&g.filter_edges(|_, &wt| wt != 2), a),
+            vec![(0, 5), (1, 0), (2, 0)]
+        );
+        assert_eq!(reach(&g.filter_vertices(|id, _| id != a), c), vec![(2, 0)]);
+        assert_eq!(
+            reach(&g.reversed(), c),
+            vec![(0, 8), (1, 1), (2, 6), (3, 0)]
+        );
+
+        let mut pg = petgraph::Graph::<(), u32>::new();
+        let n: Vec<_> = (0..3).map(|_| pg.add_node(())).collect();
+        pg.extend_with_edges([(n[0], n[1], 3), (n[1], n[2], 5), (n[2], n[1], 7)]);
+        assert_eq!(reach(&pg, n[1]), vec![(1, 5), (2, 7)]);
+        assert_eq!(pg.in_neighbors(n[1]).collect::<Vec<_>>(), vec![n[2], n[0]]);
+        assert_eq!(g.in_neighbors(c).collect::<Vec<_>>(), vec![ids["b"], a]);
+    }
+}
@@ -0,0 +1,127 @@
+use std::{cmp::Ordering, fmt, ops::Add, time::Duration};
+
+/// The weight of an edge. Weights only need to be copyable, totally ordered and addable, which is
+/// all the path algorithms ask of them.
+pub trait Weight: Copy + Ord + Add<Output = Self> {
+    /// The weight of an un-weighted edge, and the length of an empty path.
+    fn zero() -> Self;
+}
+
+/// The data stored on an edge of a [`Graph`](crate::Graph). The graph algorithms only ever look
+/// at the weight of an edge, so any payload can be put on the edges as long as it knows its
+/// weight. Every [`Weight`] is its own edge data, and so is a `(weight, payload)` pair.
+pub trait EdgeWeight {
+    type Weight: Weight;
+
+    /// The weight of the edge.
+    fn weight(&self) -> Self::Weight;
+
+    /// Replaces the weight of the edge, leaving the rest of the payload alone.
+    fn set_weight(&mut self, weight: Self::Weight);
+}
+
+impl<W: Weight> EdgeWeight for W {
+    type Weight = W;
+
+    fn weight(&self) -> W {
+        *self
+    }
+
+    fn set_weight(&mut self, weight: W) {
+        *self = weight;
+    }
+}
+
+impl<W: Weight, D> EdgeWeight for (W, D) {
+    type Weight = W;
+
+    fn weight(&self) -> W {
+        self.0
+    }
+
+    fn set_weight(&mut self, weight: W) {
+        self.0 = weight;
+    }
+}
+
+macro_rules! int_weight {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                fn zero() -> Self {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+int_weight!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl Weight for Duration {
+    fn zero() -> Self {
+        Duration::ZERO
+    }
+}
+
+/// A floating point number that is totally ordered, so that it can be used as a [`Weight`].
+/// The ordering is the one of [`f64::total_cmp`], which places `-0.0` before `0.0` and sorts
+/// the NaNs to the ends.
+#[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Total<F>(pub F);
+
+macro_rules! float_weight {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq for Total<$t> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.cmp(other) == Ordering::Equal
+                }
+            }
+
+            impl Eq for Total<$t> {}
+
+            impl PartialOrd for Total<$t> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for Total<$t> {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl Add for Total<$t> {
+                type Output = Self;
+
+                fn add(self, other: Self) -> Self {
+                    Total(self.0 + other.0)
+                }
+            }
+
+            impl From<$t> for Total<$t> {
+                fn from(f: $t) -> Self {
+                    Total(f)
+                }
+            }
+
+            impl Weight for Total<$t> {
+                fn zero() -> Self {
+                    Total(0.0)
+                }
+            }
+        )*
+    };
+}
+
+float_weight!(f32, f64);
+
+impl<F: fmt::Display> fmt::Display for Total<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}